@@ -1,8 +1,22 @@
 defmodule Cairo do
   @moduledoc """
   Documentation for `Cairo`.
+
+  The NIFs take and return binaries. The functions in this module also
+  accept lists of bytes, and return lists of bytes when given lists, so that
+  list based callers keep working.
+
+  Errors are returned as `{:error, reason}` where `reason` is an atom naming
+  the error, tagged with its details when there are any, e.g.
+  `{:error, {:register_states_error, detail}}` or
+  `{:error, {:public_input_error, %{offset: 12, reason: atom}}}`.
   """
 
+  import Cairo.Bytes,
+    only: [to_binary: 1, to_binaries: 1, to_list: 1, like: 2]
+
+  alias Cairo.Bytes
+
   @doc """
   Hello world.
 
@@ -17,101 +31,930 @@ defmodule Cairo do
   end
 
   @spec cairo_vm_runner(binary(), binary()) ::
-          {binary(), [byte()], [byte()], [byte()]} | {:error, term()}
+          {binary(), binary(), binary(), binary()} | {:error, term()}
   defdelegate cairo_vm_runner(program_content, program_input),
     to: Cairo.CairoVM,
     as: :cairo_vm_runner
 
-  @spec prove([byte()], [byte()], [byte()]) ::
-          {[byte()], [byte()]} | {:error, term()}
-  defdelegate prove(trace, memory, public_input),
+  @doc """
+  Proves the execution given by the outputs of `cairo_vm_runner/2`.
+
+  The prover constrains the range_check builtin only. The segments of the
+  bitwise, ec_op, keccak and poseidon builtins are left out of the proof,
+  so the proof does not cover the values those builtins compute. Public
+  inputs with an unknown segment type are rejected.
+  """
+  @spec prove(Bytes.t(), Bytes.t(), Bytes.t(), Cairo.ProofOptions.t()) ::
+          {Bytes.t(), Bytes.t()} | {:error, term()}
+  def prove(trace, memory, public_input, options \\ %Cairo.ProofOptions{}) do
+    Cairo.CairoProver.cairo_prove(
+      to_binary(trace),
+      to_binary(memory),
+      to_binary(public_input),
+      options
+    )
+    |> like(trace)
+  end
+
+  @doc """
+  Starts proving on a native thread pool and returns the job right away.
+
+  Once the proof is done, `{:cairo_proof, job, result}` is sent to `pid`,
+  where `result` is `{:ok, {proof, public_input}}` or `{:error, reason}`.
+  The proof and the public input are binaries.
+
+  Cancellation with `cancel_prove/1` is only checked between the proving
+  stages. A job cancelled while the proof itself is being generated keeps
+  its thread busy until that stage ends, then reports
+  `{:error, :cancelled}`.
+  """
+  @spec prove_async(
+          Bytes.t(),
+          Bytes.t(),
+          Bytes.t(),
+          Cairo.ProofOptions.t(),
+          pid()
+        ) :: reference()
+  def prove_async(
+        trace,
+        memory,
+        public_input,
+        options \\ %Cairo.ProofOptions{},
+        pid \\ self()
+      ) do
+    Cairo.CairoProver.cairo_prove_async(
+      to_binary(trace),
+      to_binary(memory),
+      to_binary(public_input),
+      options,
+      pid
+    )
+  end
+
+  @doc """
+  Cancels a job started with `prove_async/5`.
+
+  Returns `true` if the job was still running, its result is then
+  `{:error, :cancelled}`. The job stops at the end of its current proving
+  stage, see `prove_async/5`.
+  """
+  @spec cancel_prove(reference()) :: boolean()
+  defdelegate cancel_prove(job),
+    to: Cairo.CairoProver,
+    as: :cairo_prove_cancel
+
+  @doc """
+  Runs the program and proves its execution in a single native call.
+
+  Returns the program output along with the proof and the public input, the
+  same as `cairo_vm_runner/2` followed by `prove/4` would.
+  """
+  @spec run_and_prove(binary(), binary(), Cairo.ProofOptions.t()) ::
+          {binary(), binary(), binary()} | {:error, term()}
+  defdelegate run_and_prove(
+                program_content,
+                program_input,
+                options \\ %Cairo.ProofOptions{}
+              ),
+              to: Cairo.CairoProver,
+              as: :cairo_run_and_prove
+
+  @doc """
+  Converts public input bytes to a readable JSON form.
+
+  `format` is `:vm` for the public input returned by `cairo_vm_runner/2`,
+  or `:bincode` for the public input returned by `prove/4`. The VM public
+  input is laid out as follows, all integers little-endian:
+
+    * `rc_min` and `rc_max`, u16 each
+    * `mem_len`, u64, followed by `mem_len` memory cells of 40 bytes, the
+      u64 address then the 32 bytes felt value
+    * the number of segments, one byte, followed by the 17 bytes segments,
+      the type byte then the u64 `begin_addr` and `stop_ptr`
+
+  Segment types are 0 range_check, 1 output, 2 program, 3 execution,
+  4 ecdsa, 5 pedersen, 6 bitwise, 7 ec_op, 8 keccak and 9 poseidon.
+  """
+  @spec public_input_to_json(Bytes.t(), :vm | :bincode) ::
+          binary() | {:error, term()}
+  def public_input_to_json(public_input, format \\ :vm) do
+    public_input
+    |> to_binary()
+    |> Cairo.CairoProver.cairo_public_input_to_json(format)
+  end
+
+  @doc """
+  Encodes the JSON form given by `public_input_to_json/2` back to bytes.
+  """
+  @spec public_input_from_json(binary(), :vm | :bincode) ::
+          binary() | {:error, term()}
+  defdelegate public_input_from_json(json, format \\ :vm),
     to: Cairo.CairoProver,
-    as: :cairo_prove
+    as: :cairo_public_input_from_json
+
+  @doc """
+  Verifies a proof with the options recorded in it.
 
-  @spec verify(list(byte()), list(byte())) ::
+  Returns `{:error, :proof_options_mismatch}` if the recorded options are
+  weaker than `options`, which default to `%Cairo.ProofOptions{}`: a lower
+  security level, a conjecturable level where a provable one is expected,
+  or fewer bits of conjectured security.
+  """
+  @spec verify(Bytes.t(), Bytes.t(), Cairo.ProofOptions.t() | nil) ::
           boolean() | {:error, term()}
-  defdelegate verify(proof, pub_input),
+  def verify(proof, pub_input, options \\ nil) do
+    Cairo.CairoProver.cairo_verify(
+      to_binary(proof),
+      to_binary(pub_input),
+      options
+    )
+  end
+
+  @spec verify_batch(
+          list({Bytes.t(), Bytes.t()}),
+          Cairo.ProofOptions.t() | nil
+        ) ::
+          {list({:ok, boolean()} | {:error, term()}),
+           non_neg_integer() | nil}
+          | {:error, term()}
+  def verify_batch(proofs, options \\ nil) do
+    proofs
+    |> Enum.map(fn {proof, pub_input} ->
+      {to_binary(proof), to_binary(pub_input)}
+    end)
+    |> Cairo.CairoProver.cairo_verify_batch(options)
+  end
+
+  @doc """
+  Bundles the proofs of a transaction in a `Cairo.Transaction`, signed with
+  the rcvs of its compliance units.
+  """
+  @spec create_transaction(
+          list({Bytes.t(), Bytes.t()}),
+          list({Bytes.t(), Bytes.t()}),
+          list(Bytes.t())
+        ) :: Cairo.Transaction.t() | {:error, term()}
+  def create_transaction(compliance_units, logic_proofs, rcvs) do
+    Cairo.CairoProver.cairo_transaction_create(
+      proof_binaries(compliance_units),
+      proof_binaries(logic_proofs),
+      to_binaries(rcvs)
+    )
+  end
+
+  @doc """
+  Verifies a transaction.
+
+  Returns `:ok` if all its proofs verify, if its compliance units are
+  proofs of the compliance program of `native/cairo_vm/compliance.json`,
+  if every resource of its compliance units has a logic proof whose program
+  hash is the logic of the resource, and if its binding signature verifies
+  against the sum of the deltas of its compliance units. Its roots must be
+  the sorted distinct roots of its compliance units, and no nullifier or
+  commitment may appear twice in its compliance units.
+
+  Otherwise returns the error of the first failed check, e.g.
+  `{:error, {:invalid_logic_proof, index}}`,
+  `{:error, {:compliance_program_mismatch, index}}`,
+  `{:error, {:duplicate_resource, resource_id}}`,
+  `{:error, {:missing_logic_proof, resource_id}}`,
+  `{:error, {:logic_mismatch, index}}`, `{:error, :roots_mismatch}` or
+  `{:error, :invalid_binding_signature}`. Proofs that cannot be decoded
+  give `{:error, {:invalid_compliance_proof, index, reason}}`.
+  """
+  @spec verify_transaction(
+          Cairo.Transaction.t(),
+          Cairo.ProofOptions.t() | nil
+        ) :: :ok | {:error, term()}
+  defdelegate verify_transaction(transaction, options \\ nil),
     to: Cairo.CairoProver,
-    as: :cairo_verify
+    as: :cairo_transaction_verify
+
+  @doc """
+  Merges two partial transactions into one, signed with the rcvs of the
+  compliance units of both. The binding private key is the sum of the rcvs,
+  as in `sign/2`, so the merged transaction verifies once the
+  combined transaction is balanced.
 
-  @spec get_output(list(byte())) ::
-          any() | {:error, term()}
-  defdelegate get_output(pub_input),
+  The roots of both must match their compliance units, otherwise
+  `{:error, :roots_mismatch}`, and they must not share a nullifier or
+  commitment, otherwise `{:error, {:duplicate_resource, resource_id}}`.
+  Their proofs and binding signatures are not checked, a partial
+  transaction being usually unbalanced, see `verify_transaction/2` for the
+  merged transaction.
+  """
+  @spec merge_transactions(
+          Cairo.Transaction.t(),
+          Cairo.Transaction.t(),
+          list(Bytes.t())
+        ) :: Cairo.Transaction.t() | {:error, term()}
+  def merge_transactions(transaction, other, private_key_segments) do
+    Cairo.CairoProver.cairo_transaction_merge(
+      transaction,
+      other,
+      to_binaries(private_key_segments)
+    )
+  end
+
+  @doc """
+  Encodes a transaction as a binary: the `"AATX"` magic and a little-endian
+  u16 format version, followed by its proofs, public inputs, binding
+  signature and roots.
+  """
+  @spec transaction_to_bytes(Cairo.Transaction.t()) ::
+          binary() | {:error, term()}
+  defdelegate transaction_to_bytes(transaction),
     to: Cairo.CairoProver,
-    as: :cairo_get_output
+    as: :cairo_transaction_to_bytes
+
+  @doc """
+  Decodes a transaction encoded by `transaction_to_bytes/1`.
 
-  @spec sign(list(byte()), list(list(byte()))) ::
-          list(byte()) | {:error, term()}
-  defdelegate sign(private_key_segments, messages),
+  Returns `{:error, {:decoding_error, reason}}` for an unknown header or
+  format version, or a truncated body or one followed by trailing bytes.
+  """
+  @spec transaction_from_bytes(binary()) ::
+          Cairo.Transaction.t() | {:error, term()}
+  defdelegate transaction_from_bytes(bytes),
     to: Cairo.CairoProver,
-    as: :cairo_binding_sig_sign
+    as: :cairo_transaction_from_bytes
+
+  @spec get_output(Bytes.t()) :: list(Bytes.t()) | {:error, term()}
+  def get_output(pub_input) do
+    pub_input
+    |> to_binary()
+    |> Cairo.CairoProver.cairo_get_output()
+    |> like(pub_input)
+  end
+
+  @doc """
+  Decodes the output of the compliance program from its public input.
+  """
+  @spec get_compliance_output(Bytes.t()) ::
+          Cairo.ComplianceResult.t() | {:error, term()}
+  def get_compliance_output(pub_input) do
+    pub_input
+    |> to_binary()
+    |> Cairo.CairoProver.cairo_get_compliance_output()
+    |> like(pub_input)
+  end
+
+  @doc """
+  Decodes the output of a resource logic program from its public input.
+  """
+  @spec get_logic_output(Bytes.t()) ::
+          Cairo.LogicResult.t() | {:error, term()}
+  def get_logic_output(pub_input) do
+    pub_input
+    |> to_binary()
+    |> Cairo.CairoProver.cairo_get_logic_output()
+    |> like(pub_input)
+  end
+
+  @spec sign(Bytes.t(), list(Bytes.t())) :: Bytes.t() | {:error, term()}
+  def sign(private_key_segments, messages) do
+    Cairo.CairoProver.cairo_binding_sig_sign(
+      to_binary(private_key_segments),
+      to_binaries(messages)
+    )
+    |> like(private_key_segments)
+  end
+
+  @doc """
+  Verifies a binding signature against the sum of the public key segments.
+  The signature is either the 64 bytes of `sign/2` or the 65 bytes of
+  `sign_recoverable/2`.
+  """
+  @spec sig_verify(list(Bytes.t()), list(Bytes.t()), Bytes.t()) ::
+          boolean() | {:error, term()}
+  def sig_verify(pub_key_segments, messages, signature) do
+    Cairo.CairoProver.cairo_binding_sig_verify(
+      to_binaries(pub_key_segments),
+      to_binaries(messages),
+      to_binary(signature)
+    )
+  end
+
+  @doc """
+  Signs like `sign/2`, with a 65 bytes signature `r || s || v` from which
+  `recover_public_key/2` recovers the public key.
+  """
+  @spec sign_recoverable(Bytes.t(), list(Bytes.t())) ::
+          Bytes.t() | {:error, term()}
+  def sign_recoverable(private_key_segments, messages) do
+    Cairo.CairoProver.cairo_binding_sig_sign_recoverable(
+      to_binary(private_key_segments),
+      to_binaries(messages)
+    )
+    |> like(private_key_segments)
+  end
+
+  @doc """
+  Recovers the x coordinate of the public key of a signature of
+  `sign_recoverable/2`, the x coordinate of the sum of the deltas of the
+  compliance units for a binding signature.
+  """
+  @spec recover_public_key(list(Bytes.t()), Bytes.t()) ::
+          Bytes.t() | {:error, term()}
+  def recover_public_key(messages, signature) do
+    Cairo.CairoProver.cairo_binding_sig_recover(
+      to_binaries(messages),
+      to_binary(signature)
+    )
+    |> like(signature)
+  end
+
+  @doc """
+  Signs the messages with a Schnorr binding signature, an alternative to
+  `sign/2`. The private key is the sum of the segments as in `sign/2`.
+
+  The 96 bytes signature is the nonce commitment `R`, as its x and y
+  coordinates, followed by `s`, with `s * G == R + e * P` for the public
+  key `P` and the Poseidon challenge `e` of `R`, `P` and the messages.
+  """
+  @spec schnorr_sign(Bytes.t(), list(Bytes.t())) ::
+          Bytes.t() | {:error, term()}
+  def schnorr_sign(private_key_segments, messages) do
+    Cairo.CairoProver.cairo_schnorr_sign(
+      to_binary(private_key_segments),
+      to_binaries(messages)
+    )
+    |> like(private_key_segments)
+  end
 
-  @spec sig_verify(list(list(byte())), list(list(byte())), list(byte())) ::
+  @doc """
+  Verifies a Schnorr binding signature against the sum of the public key
+  segments.
+  """
+  @spec schnorr_verify(list(Bytes.t()), list(Bytes.t()), Bytes.t()) ::
           boolean() | {:error, term()}
-  defdelegate sig_verify(pub_key_segments, messages, signature),
+  def schnorr_verify(pub_key_segments, messages, signature) do
+    Cairo.CairoProver.cairo_schnorr_verify(
+      to_binaries(pub_key_segments),
+      to_binaries(messages),
+      to_binary(signature)
+    )
+  end
+
+  @doc """
+  Returns a random `{nonce, commitment}` for `schnorr_partial_sign/5`.
+
+  The nonce is a pair of secret nonces, 64 bytes, and the commitment the
+  pair of their points, 128 bytes. Each signer shares its commitment with
+  the others and keeps its nonce. A nonce must be used for one partial
+  signature only: never reuse it, and never use it in concurrent signing
+  sessions, as either reveals the private key.
+  """
+  @spec schnorr_nonce() :: {binary(), binary()} | {:error, term()}
+  defdelegate schnorr_nonce(),
     to: Cairo.CairoProver,
-    as: :cairo_binding_sig_verify
+    as: :cairo_schnorr_nonce
+
+  @doc """
+  Computes the partial Schnorr signature of one signer, over the nonce
+  commitments and public key segments of all the signers. The partial
+  signatures of all the signers are combined by `schnorr_aggregate/4` into
+  a signature that verifies against the sum of the public key segments.
+
+  As in MuSig2, the nonce commitment of the signature combines the two
+  nonces of each signer with a coefficient that hashes all the
+  commitments, the public key and the messages, so that a signer cannot
+  choose its commitment against the others.
+
+  Unlike MuSig2, the public key segments are summed without key
+  aggregation coefficients, as binding signatures verify against the sum
+  of the deltas of the compliance units. The segments must be those
+  deltas, which the compliance proofs commit to: with keys chosen by the
+  signers, one of them could choose its key against the others' and sign
+  alone for their sum. A nonce that is not 64 bytes of two felts gives
+  `{:error, {:signature_generation_error, reason}}`.
+  """
+  @spec schnorr_partial_sign(
+          Bytes.t(),
+          Bytes.t(),
+          list(Bytes.t()),
+          list(Bytes.t()),
+          list(Bytes.t())
+        ) :: Bytes.t() | {:error, term()}
+  def schnorr_partial_sign(
+        private_key_segments,
+        nonce,
+        nonce_commitments,
+        pub_key_segments,
+        messages
+      ) do
+    Cairo.CairoProver.cairo_schnorr_partial_sign(
+      to_binary(private_key_segments),
+      to_binary(nonce),
+      to_binaries(nonce_commitments),
+      to_binaries(pub_key_segments),
+      to_binaries(messages)
+    )
+    |> like(private_key_segments)
+  end
+
+  @doc """
+  Aggregates the partial signatures of `schnorr_partial_sign/5` into a
+  Schnorr binding signature, given the same nonce commitments, public key
+  segments and messages.
+  """
+  @spec schnorr_aggregate(
+          list(Bytes.t()),
+          list(Bytes.t()),
+          list(Bytes.t()),
+          list(Bytes.t())
+        ) :: Bytes.t() | {:error, term()}
+  def schnorr_aggregate(
+        nonce_commitments,
+        pub_key_segments,
+        messages,
+        partial_signatures
+      ) do
+    Cairo.CairoProver.cairo_schnorr_aggregate(
+      to_binaries(nonce_commitments),
+      to_binaries(pub_key_segments),
+      to_binaries(messages),
+      to_binaries(partial_signatures)
+    )
+  end
+
+  @doc """
+  Returns a random felt as a list of bytes, see `random_felt_binary/0` to
+  get it as a binary.
+  """
+  @spec random_felt() :: list(byte()) | {:error, term()}
+  def random_felt() do
+    Cairo.CairoProver.cairo_random_felt() |> to_list()
+  end
 
-  @spec random_felt() ::
-          list(byte()) | {:error, term()}
-  defdelegate random_felt(),
+  @doc """
+  Returns a random felt as a binary, like the other functions of this
+  module when given binaries.
+  """
+  @spec random_felt_binary() :: binary() | {:error, term()}
+  defdelegate random_felt_binary(),
     to: Cairo.CairoProver,
     as: :cairo_random_felt
 
-  @spec get_public_key(list(byte())) ::
-          list(byte()) | {:error, term()}
-  defdelegate get_public_key(priv_key),
+  @spec get_public_key(Bytes.t()) :: Bytes.t() | {:error, term()}
+  def get_public_key(priv_key) do
+    priv_key
+    |> to_binary()
+    |> Cairo.CairoProver.cairo_get_binding_sig_public_key()
+    |> like(priv_key)
+  end
+
+  @spec poseidon_single(Bytes.t()) :: Bytes.t() | {:error, term()}
+  def poseidon_single(input) do
+    input |> to_binary() |> Cairo.CairoProver.poseidon_single() |> like(input)
+  end
+
+  @spec poseidon(Bytes.t(), Bytes.t()) :: Bytes.t() | {:error, term()}
+  def poseidon(x, y) do
+    Cairo.CairoProver.poseidon(to_binary(x), to_binary(y)) |> like(x)
+  end
+
+  @spec poseidon_many(list(Bytes.t())) :: Bytes.t() | {:error, term()}
+  def poseidon_many(inputs) do
+    inputs
+    |> to_binaries()
+    |> Cairo.CairoProver.poseidon_many()
+    |> like(List.first(inputs, []))
+  end
+
+  @doc """
+  Returns the nullifier public key of a nullifier key, the npk of the
+  resources it can consume.
+  """
+  @spec resource_npk(Bytes.t()) :: Bytes.t() | {:error, term()}
+  def resource_npk(nf_key) do
+    nf_key
+    |> to_binary()
+    |> Cairo.CairoProver.cairo_resource_npk()
+    |> like(nf_key)
+  end
+
+  @doc """
+  Resources are given as 225 bytes: logic, label, quantity, data, nonce,
+  npk and rseed as 32 bytes felts, then the eph byte.
+  """
+  @spec resource_psi(Bytes.t()) :: Bytes.t() | {:error, term()}
+  def resource_psi(resource) do
+    resource
+    |> to_binary()
+    |> Cairo.CairoProver.cairo_resource_psi()
+    |> like(resource)
+  end
+
+  @spec resource_rcm(Bytes.t()) :: Bytes.t() | {:error, term()}
+  def resource_rcm(resource) do
+    resource
+    |> to_binary()
+    |> Cairo.CairoProver.cairo_resource_rcm()
+    |> like(resource)
+  end
+
+  @doc """
+  Computes the commitment of a resource with its own nonce. The compliance
+  program takes the input nullifier as the nonce of the output resource,
+  see `resource_commitment_as_output/2` for the commitment it outputs.
+  """
+  @spec resource_commitment(Bytes.t()) :: Bytes.t() | {:error, term()}
+  def resource_commitment(resource) do
+    resource
+    |> to_binary()
+    |> Cairo.CairoProver.cairo_resource_commitment()
+    |> like(resource)
+  end
+
+  @doc """
+  Computes the commitment of a resource as the output of a compliance unit,
+  the `output_cm` of its `Cairo.ComplianceResult`: the nonce of the
+  resource is replaced by the input nullifier of the unit.
+  """
+  @spec resource_commitment_as_output(Bytes.t(), Bytes.t()) ::
+          Bytes.t() | {:error, term()}
+  def resource_commitment_as_output(resource, input_nullifier) do
+    Cairo.CairoProver.cairo_resource_commitment_as_output(
+      to_binary(resource),
+      to_binary(input_nullifier)
+    )
+    |> like(resource)
+  end
+
+  @doc """
+  Computes the nullifier of a resource, `nf_key` must match the npk of the
+  resource.
+  """
+  @spec resource_nullifier(Bytes.t(), Bytes.t()) ::
+          Bytes.t() | {:error, term()}
+  def resource_nullifier(resource, nf_key) do
+    Cairo.CairoProver.cairo_resource_nullifier(
+      to_binary(resource),
+      to_binary(nf_key)
+    )
+    |> like(resource)
+  end
+
+  @doc """
+  Returns the kind of a resource, the curve point hashed from its logic and
+  label, as the 64 bytes of its x and y coordinates.
+  """
+  @spec resource_kind(Bytes.t()) :: Bytes.t() | {:error, term()}
+  def resource_kind(resource) do
+    resource
+    |> to_binary()
+    |> Cairo.CairoProver.cairo_resource_kind()
+    |> like(resource)
+  end
+
+  @doc """
+  Returns the kind of a resource times its quantity.
+  """
+  @spec resource_delta(Bytes.t()) :: Bytes.t() | {:error, term()}
+  def resource_delta(resource) do
+    resource
+    |> to_binary()
+    |> Cairo.CairoProver.cairo_resource_delta()
+    |> like(resource)
+  end
+
+  @doc """
+  Computes the delta of a transaction: the deltas of the input resources
+  minus the ones of the output resources, blinded by the rcv values of its
+  compliance units. A single compliance unit gives the delta_x and delta_y
+  of its compliance program.
+  """
+  @spec transaction_delta(
+          list(Bytes.t()),
+          list(Bytes.t()),
+          list(Bytes.t())
+        ) :: Bytes.t() | {:error, term()}
+  def transaction_delta(inputs, outputs, rcvs) do
+    Cairo.CairoProver.cairo_transaction_delta(
+      to_binaries(inputs),
+      to_binaries(outputs),
+      to_binaries(rcvs)
+    )
+    |> like(List.first(rcvs, []))
+  end
+
+  @doc """
+  Creates an ephemeral resource with a fresh nonce, rseed and nf_key.
+
+  Returns `{resource, nf_key}`. Ephemeral inputs are not in the commitment
+  tree, use `Cairo.ComplianceInput.ephemeral/4` to consume them.
+  """
+  @spec ephemeral_resource(Bytes.t(), Bytes.t(), Bytes.t(), Bytes.t()) ::
+          {binary(), binary()} | {:error, term()}
+  def ephemeral_resource(logic, label, quantity, data \\ <<0::256>>) do
+    Cairo.CairoProver.cairo_ephemeral_resource(
+      to_binary(logic),
+      to_binary(label),
+      to_binary(quantity),
+      to_binary(data)
+    )
+  end
+
+  @doc """
+  Returns the root output by the compliance program when its input is
+  ephemeral: the root of the empty commitment tree, which every tree knows.
+  """
+  @spec eph_root() :: binary()
+  defdelegate eph_root(), to: Cairo.CairoProver, as: :cairo_eph_root
+
+  @doc """
+  Returns the Merkle path given to the compliance program when its input is
+  ephemeral, the path of the first leaf of the empty tree.
+  """
+  @spec dummy_merkle_path() :: [binary()]
+  defdelegate dummy_merkle_path(),
+    to: Cairo.CairoProver,
+    as: :cairo_dummy_merkle_path
+
+  @doc """
+  Creates an empty commitment tree.
+
+  The tree is a Poseidon Merkle tree of depth 32 whose empty leaves are
+  zero. Its nodes are hashed the way the compliance program checks Merkle
+  paths, so the root of a tree holding the commitment of an input resource
+  is the root output by the compliance program.
+  """
+  @spec merkle_tree_new() :: reference()
+  defdelegate merkle_tree_new(),
+    to: Cairo.CairoProver,
+    as: :cairo_merkle_tree_new
+
+  @doc """
+  Appends a commitment to the tree and returns its position.
+  """
+  @spec merkle_tree_append(reference(), Bytes.t()) ::
+          non_neg_integer() | {:error, term()}
+  def merkle_tree_append(tree, commitment) do
+    Cairo.CairoProver.cairo_merkle_tree_append(tree, to_binary(commitment))
+  end
+
+  @spec merkle_tree_root(reference()) :: binary()
+  defdelegate merkle_tree_root(tree),
+    to: Cairo.CairoProver,
+    as: :cairo_merkle_tree_root
+
+  @doc """
+  Returns the number of commitments in the tree.
+  """
+  @spec merkle_tree_size(reference()) :: non_neg_integer()
+  defdelegate merkle_tree_size(tree),
+    to: Cairo.CairoProver,
+    as: :cairo_merkle_tree_size
+
+  @doc """
+  Returns the authentication path of the commitment at `position`: the
+  siblings of its nodes, from the leaf up. The bits of the position tell
+  whether each sibling is on the left, as expected by
+  `generate_compliance_input_json/1`.
+  """
+  @spec merkle_tree_path(reference(), non_neg_integer()) ::
+          [binary()] | {:error, term()}
+  defdelegate merkle_tree_path(tree, position),
+    to: Cairo.CairoProver,
+    as: :cairo_merkle_tree_path
+
+  @doc """
+  Returns whether `root` is one of the last 100 roots of the tree, or
+  `eph_root/0`, which is known however many commitments were appended.
+  """
+  @spec merkle_tree_known_root?(reference(), Bytes.t()) ::
+          boolean() | {:error, term()}
+  def merkle_tree_known_root?(tree, root) do
+    Cairo.CairoProver.cairo_merkle_tree_known_root(tree, to_binary(root))
+  end
+
+  @doc """
+  Checks that the commitment at `position` is in the tree of root `root`.
+  """
+  @spec merkle_verify(
+          Bytes.t(),
+          Bytes.t(),
+          list(Bytes.t()),
+          non_neg_integer()
+        ) :: boolean() | {:error, term()}
+  def merkle_verify(root, commitment, path, position) do
+    Cairo.CairoProver.cairo_merkle_verify(
+      to_binary(root),
+      to_binary(commitment),
+      to_binaries(path),
+      position
+    )
+  end
+
+  @doc """
+  Creates the frontier of an empty commitment tree.
+
+  A frontier holds the size of a tree, its last commitment and the nodes
+  needed to append commitments to it, not the other commitments. It is a
+  binary of fixed size that can be stored as is, its root is recomputed
+  when it is read. Its roots and paths are the ones of the tree created by
+  `merkle_tree_new/0` with the same commitments.
+  """
+  @spec merkle_frontier_new() :: binary()
+  defdelegate merkle_frontier_new(),
     to: Cairo.CairoProver,
-    as: :cairo_get_binding_sig_public_key
+    as: :cairo_merkle_frontier_new
+
+  @doc """
+  Appends a commitment, returns the new frontier and the position of the
+  commitment.
+  """
+  @spec merkle_frontier_append(binary(), Bytes.t()) ::
+          {binary(), non_neg_integer()} | {:error, term()}
+  def merkle_frontier_append(frontier, commitment) do
+    Cairo.CairoProver.cairo_merkle_frontier_append(
+      frontier,
+      to_binary(commitment)
+    )
+  end
 
-  @spec poseidon_single(list(byte())) ::
-          list(byte()) | {:error, term()}
-  defdelegate poseidon_single(input),
+  @spec merkle_frontier_root(binary()) :: binary() | {:error, term()}
+  defdelegate merkle_frontier_root(frontier),
     to: Cairo.CairoProver,
-    as: :poseidon_single
+    as: :cairo_merkle_frontier_root
 
-  @spec poseidon(list(byte()), list(byte())) ::
-          list(byte()) | {:error, term()}
-  defdelegate poseidon(x, y),
+  @spec merkle_frontier_size(binary()) ::
+          non_neg_integer() | {:error, term()}
+  defdelegate merkle_frontier_size(frontier),
     to: Cairo.CairoProver,
-    as: :poseidon
+    as: :cairo_merkle_frontier_size
 
-  @spec poseidon_many(list(list(byte()))) ::
-          list(byte()) | {:error, term()}
-  defdelegate poseidon_many(inputs),
+  @doc """
+  Returns the authentication path of the last commitment appended to the
+  frontier, see `merkle_tree_path/2`.
+  """
+  @spec merkle_frontier_path(binary()) :: [binary()] | {:error, term()}
+  defdelegate merkle_frontier_path(frontier),
     to: Cairo.CairoProver,
-    as: :poseidon_many
+    as: :cairo_merkle_frontier_path
+
+  @spec get_program_hash(Bytes.t()) :: Bytes.t() | {:error, term()}
+  def get_program_hash(pub_input) do
+    pub_input
+    |> to_binary()
+    |> Cairo.CairoProver.program_hash()
+    |> like(pub_input)
+  end
 
-  @spec get_program_hash(list(byte())) ::
-          list(byte()) | {:error, term()}
-  defdelegate get_program_hash(pub_input),
+  @doc """
+  Computes the program hash from a compiled program JSON, without running or
+  proving it. It is the hash `get_program_hash/1` returns for the proofs of
+  the program.
+  """
+  @spec get_program_hash_from_json(binary()) :: binary() | {:error, term()}
+  defdelegate get_program_hash_from_json(program_content),
     to: Cairo.CairoProver,
-    as: :program_hash
+    as: :program_hash_from_json
+
+  @spec felt_to_string(Bytes.t()) :: binary() | {:error, term()}
+  def felt_to_string(felt) do
+    felt |> to_binary() |> Cairo.CairoProver.cairo_felt_to_string()
+  end
+
+  @doc """
+  Encodes the inputs of the compliance program for `cairo_vm_runner/2`.
+
+  `input` is a `Cairo.ComplianceInput` or a map of its fields. The inputs
+  are checked before running the program: the resources must be 225 bytes
+  of valid felts, the npk of the input resource must be derived from
+  `input_nf_key`, and the Merkle path must have 32 nodes, the depth of the
+  commitment tree, with a position that fits in it.
+  """
+  @spec generate_compliance_input_json(Cairo.ComplianceInput.t() | map()) ::
+          binary() | {:error, term()}
+  def generate_compliance_input_json(%Cairo.ComplianceInput{} = input) do
+    %Cairo.ComplianceInput{
+      input
+      | input_resource: to_binary(input.input_resource),
+        output_resource: to_binary(input.output_resource),
+        merkle_path: to_binaries(input.merkle_path),
+        input_nf_key: to_binary(input.input_nf_key),
+        eph_root: to_binary(input.eph_root),
+        rcv: to_binary(input.rcv)
+    }
+    |> Cairo.CairoProver.cairo_generate_compliance_input_json()
+  end
+
+  def generate_compliance_input_json(input) do
+    with {:ok, input} <- Cairo.ComplianceInput.new(input) do
+      generate_compliance_input_json(input)
+    end
+  end
+
+  @doc """
+  Generates the compliance inputs of a transaction.
+
+  Each input is a map of the `:resource`, its `:nf_key`, and the
+  `:merkle_path` and `:position` of its commitment. The inputs and the
+  outputs are paired in compliance units, the shorter list is padded with
+  ephemeral resources of quantity zero. The logic of these resources is
+  the one of `native/cairo_vm/trivial_resource_logic.json`, and a
+  transaction needs its proof for them like for any other resource. The
+  root of the units whose input is ephemeral is `eph_root/0`.
+
+  Returns a `{json, rcv, padding}` tuple per unit, with a fresh rcv. The
+  rcvs are the private key segments of the binding signature. `padding` is
+  a map of the padding `:input` and `:output` of the unit, each a
+  `{resource, nf_key}` tuple or `nil`, for their logic proofs. As for any
+  output, the compliance program takes the input nullifier as the nonce of
+  a padding output.
+  """
+  @spec generate_compliance_units_json(list(map()), list(Bytes.t())) ::
+          [{binary(), binary(), map()}] | {:error, term()}
+  def generate_compliance_units_json(inputs, outputs) do
+    inputs =
+      Enum.map(inputs, fn input ->
+        %{
+          resource: to_binary(input.resource),
+          nf_key: to_binary(input.nf_key),
+          merkle_path: to_binaries(input.merkle_path),
+          position: input.position
+        }
+      end)
+
+    Cairo.CairoProver.cairo_generate_compliance_units_json(
+      inputs,
+      to_binaries(outputs)
+    )
+  end
 
-  @spec felt_to_string(list(byte())) :: binary()
-  defdelegate felt_to_string(felt),
+  @doc """
+  Parses a compliance input JSON, e.g. a stored witness.
+
+  Returns a map with the `:input` and `:output` resources, as maps of their
+  fields, and the `:input_nf_key`, `:merkle_path`, `:rcv` and `:eph_root`.
+  Felts are 32 bytes binaries, from any hex string below the field prime,
+  and the Merkle path is a list of `{node, is_left}` tuples. Keys other
+  than these are ignored. The `:source` is the parsed JSON.
+
+  `encode_compliance_input_json/1` encodes the map back to its source
+  byte for byte: the whitespace, the key order, the ignored keys and the
+  hex strings of unchanged felts are kept, and changed fields are written
+  in place.
+  """
+  @spec parse_compliance_input_json(binary()) :: map() | {:error, term()}
+  defdelegate parse_compliance_input_json(json),
     to: Cairo.CairoProver,
-    as: :cairo_felt_to_string
+    as: :cairo_parse_compliance_input_json
+
+  @doc """
+  Encodes a map returned by `parse_compliance_input_json/1`, possibly
+  modified, in the formatting of its `:source` described there. Without a
+  `:source`, or if the fields no longer fit it, e.g. with a longer Merkle
+  path, the JSON is compact with the keys in the order of
+  `parse_compliance_input_json/1`, as generated by
+  `generate_compliance_input_json/1`. Unlike the latter, it only checks
+  that the felts are valid.
+  """
+  @spec encode_compliance_input_json(map()) :: binary() | {:error, term()}
+  def encode_compliance_input_json(input) do
+    input
+    |> Map.put_new(:source, nil)
+    |> Cairo.CairoProver.cairo_encode_compliance_input_json()
+  end
 
+  @doc """
+  Same as `generate_compliance_input_json/1` with positional inputs.
+  """
   @spec generate_compliance_input_json(
-          list(byte()),
-          list(byte()),
-          list(list(byte())),
-          integer(),
-          list(byte()),
-          list(byte()),
-          list(byte())
+          Bytes.t(),
+          Bytes.t(),
+          list(Bytes.t()),
+          non_neg_integer(),
+          Bytes.t(),
+          Bytes.t(),
+          Bytes.t()
         ) ::
-          binary()
-  defdelegate generate_compliance_input_json(
-                input_resource,
-                output_resource,
-                path,
-                position,
-                input_nf_key,
-                eph_root,
-                rcv
-              ),
-              to: Cairo.CairoProver,
-              as: :cairo_generate_compliance_input_json
+          binary() | {:error, term()}
+  def generate_compliance_input_json(
+        input_resource,
+        output_resource,
+        path,
+        position,
+        input_nf_key,
+        eph_root,
+        rcv
+      ) do
+    generate_compliance_input_json(%Cairo.ComplianceInput{
+      input_resource: input_resource,
+      output_resource: output_resource,
+      merkle_path: path,
+      position: position,
+      input_nf_key: input_nf_key,
+      eph_root: eph_root,
+      rcv: rcv
+    })
+  end
+
+  defp proof_binaries(proofs) do
+    Enum.map(proofs, fn {proof, public_input} ->
+      {to_binary(proof), to_binary(public_input)}
+    end)
+  end
 end
@@ -0,0 +1,49 @@
+defmodule Cairo.Bytes do
+  @moduledoc """
+  Conversions between the binaries taken and returned by the NIFs and the
+  lists of bytes used by earlier versions of the `Cairo` API.
+  """
+
+  @type t() :: binary() | [byte()]
+
+  @doc """
+  Converts a list of bytes to a binary, binaries are returned as is.
+  """
+  @spec to_binary(t()) :: binary()
+  def to_binary(bytes) when is_binary(bytes), do: bytes
+  def to_binary(bytes) when is_list(bytes), do: :erlang.list_to_binary(bytes)
+
+  @spec to_binaries([t()]) :: [binary()]
+  def to_binaries(list), do: Enum.map(list, &to_binary/1)
+
+  @doc """
+  Converts the binaries in a NIF result to lists of bytes. Errors are left
+  untouched.
+  """
+  @spec to_list(term()) :: term()
+  def to_list({:error, _} = error), do: error
+  def to_list(bytes) when is_binary(bytes), do: :binary.bin_to_list(bytes)
+  def to_list(list) when is_list(list), do: Enum.map(list, &to_list/1)
+
+  def to_list(tuple) when is_tuple(tuple) do
+    tuple |> Tuple.to_list() |> Enum.map(&to_list/1) |> List.to_tuple()
+  end
+
+  def to_list(%module{} = struct) do
+    struct(module, struct |> Map.from_struct() |> to_list())
+  end
+
+  def to_list(map) when is_map(map) do
+    Map.new(map, fn {key, value} -> {key, to_list(value)} end)
+  end
+
+  def to_list(other), do: other
+
+  @doc """
+  Returns the result as lists of bytes if the caller gave `input` as a list
+  of bytes, and as binaries otherwise.
+  """
+  @spec like(term(), t() | [t()]) :: term()
+  def like(result, input) when is_list(input), do: to_list(result)
+  def like(result, _input), do: result
+end
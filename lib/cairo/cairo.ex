@@ -10,59 +10,287 @@ defmodule Cairo.CairoProver do
   @typedoc "Result type for NIF functions that can return errors"
   @type nif_result(t) :: t | {:error, term()}
 
-  @spec cairo_prove(list(byte()), list(byte()), list(byte())) ::
-          nif_result({list(byte()), list(byte())})
-  def cairo_prove(_trace, _memory, _public_input), do: error()
+  @spec cairo_prove(binary(), binary(), binary(), Cairo.ProofOptions.t()) ::
+          nif_result({binary(), binary()})
+  def cairo_prove(
+        _trace,
+        _memory,
+        _public_input,
+        _options \\ %Cairo.ProofOptions{}
+      ),
+      do: error()
+
+  @spec cairo_prove_async(
+          binary(),
+          binary(),
+          binary(),
+          Cairo.ProofOptions.t(),
+          pid()
+        ) :: reference()
+  def cairo_prove_async(_trace, _memory, _public_input, _options, _pid),
+    do: error()
+
+  @spec cairo_prove_cancel(reference()) :: boolean()
+  def cairo_prove_cancel(_job), do: error()
+
+  @spec cairo_run_and_prove(binary(), binary(), Cairo.ProofOptions.t()) ::
+          nif_result({binary(), binary(), binary()})
+  def cairo_run_and_prove(
+        _program_content,
+        _program_input,
+        _options \\ %Cairo.ProofOptions{}
+      ),
+      do: error()
+
+  @spec cairo_public_input_to_json(binary(), :vm | :bincode) ::
+          nif_result(binary())
+  def cairo_public_input_to_json(_public_input, _format), do: error()
+
+  @spec cairo_public_input_from_json(binary(), :vm | :bincode) ::
+          nif_result(binary())
+  def cairo_public_input_from_json(_json, _format), do: error()
+
+  @spec cairo_verify(binary(), binary(), Cairo.ProofOptions.t() | nil) ::
+          nif_result(boolean())
+  def cairo_verify(_proof, _pubinput, _options \\ nil), do: error()
+
+  @spec cairo_transaction_create(
+          list({binary(), binary()}),
+          list({binary(), binary()}),
+          list(binary())
+        ) :: nif_result(Cairo.Transaction.t())
+  def cairo_transaction_create(_compliance_units, _logic_proofs, _rcvs),
+    do: error()
+
+  @spec cairo_transaction_verify(
+          Cairo.Transaction.t(),
+          Cairo.ProofOptions.t() | nil
+        ) :: :ok | {:error, term()}
+  def cairo_transaction_verify(_transaction, _options), do: error()
+
+  @spec cairo_transaction_merge(
+          Cairo.Transaction.t(),
+          Cairo.Transaction.t(),
+          list(binary())
+        ) :: nif_result(Cairo.Transaction.t())
+  def cairo_transaction_merge(_transaction, _other, _private_key_segments),
+    do: error()
+
+  @spec cairo_transaction_to_bytes(Cairo.Transaction.t()) ::
+          nif_result(binary())
+  def cairo_transaction_to_bytes(_transaction), do: error()
 
-  @spec cairo_verify(list(byte()), list(byte())) :: nif_result(boolean())
-  def cairo_verify(_proof, _pubinput), do: error()
+  @spec cairo_transaction_from_bytes(binary()) ::
+          nif_result(Cairo.Transaction.t())
+  def cairo_transaction_from_bytes(_bytes), do: error()
 
-  @spec cairo_get_output(list(byte())) :: nif_result(list(list(byte())))
+  @spec cairo_verify_batch(
+          list({binary(), binary()}),
+          Cairo.ProofOptions.t() | nil
+        ) ::
+          nif_result(
+            {list({:ok, boolean()} | {:error, term()}),
+             non_neg_integer() | nil}
+          )
+  def cairo_verify_batch(_proofs, _options \\ nil), do: error()
+
+  @spec cairo_get_output(binary()) :: nif_result(list(binary()))
   def cairo_get_output(_public_input), do: error()
 
-  @spec cairo_binding_sig_sign(list(list(byte())), list(list(byte()))) ::
-          nif_result(list(byte()))
+  @spec cairo_get_compliance_output(binary()) ::
+          nif_result(Cairo.ComplianceResult.t())
+  def cairo_get_compliance_output(_public_input), do: error()
+
+  @spec cairo_get_logic_output(binary()) :: nif_result(Cairo.LogicResult.t())
+  def cairo_get_logic_output(_public_input), do: error()
+
+  @spec cairo_binding_sig_sign(binary(), list(binary())) ::
+          nif_result(binary())
   def cairo_binding_sig_sign(_private_key_segments, _messages), do: error()
 
-  @spec cairo_binding_sig_verify(
-          list(list(byte())),
-          list(list(byte())),
-          list(byte())
-        ) :: nif_result(boolean())
+  @spec cairo_binding_sig_verify(list(binary()), list(binary()), binary()) ::
+          nif_result(boolean())
   def cairo_binding_sig_verify(_pub_key_segments, _messages, _signature),
     do: error()
 
-  @spec cairo_random_felt() :: nif_result(list(byte()))
+  @spec cairo_binding_sig_sign_recoverable(binary(), list(binary())) ::
+          nif_result(binary())
+  def cairo_binding_sig_sign_recoverable(_private_key_segments, _messages),
+    do: error()
+
+  @spec cairo_binding_sig_recover(list(binary()), binary()) ::
+          nif_result(binary())
+  def cairo_binding_sig_recover(_messages, _signature), do: error()
+
+  @spec cairo_schnorr_sign(binary(), list(binary())) :: nif_result(binary())
+  def cairo_schnorr_sign(_private_key_segments, _messages), do: error()
+
+  @spec cairo_schnorr_verify(list(binary()), list(binary()), binary()) ::
+          nif_result(boolean())
+  def cairo_schnorr_verify(_pub_key_segments, _messages, _signature),
+    do: error()
+
+  @spec cairo_schnorr_nonce() :: nif_result({binary(), binary()})
+  def cairo_schnorr_nonce(), do: error()
+
+  @spec cairo_schnorr_partial_sign(
+          binary(),
+          binary(),
+          list(binary()),
+          list(binary()),
+          list(binary())
+        ) :: nif_result(binary())
+  def cairo_schnorr_partial_sign(
+        _private_key_segments,
+        _nonce,
+        _nonce_commitments,
+        _pub_key_segments,
+        _messages
+      ),
+      do: error()
+
+  @spec cairo_schnorr_aggregate(
+          list(binary()),
+          list(binary()),
+          list(binary()),
+          list(binary())
+        ) :: nif_result(binary())
+  def cairo_schnorr_aggregate(
+        _nonce_commitments,
+        _pub_key_segments,
+        _messages,
+        _partial_signatures
+      ),
+      do: error()
+
+  @spec cairo_random_felt() :: nif_result(binary())
   def cairo_random_felt(), do: error()
 
-  @spec cairo_get_binding_sig_public_key(list(byte())) ::
-          nif_result(list(byte()))
+  @spec cairo_get_binding_sig_public_key(binary()) :: nif_result(binary())
   def cairo_get_binding_sig_public_key(_priv_key), do: error()
 
-  @spec poseidon_single(list(byte())) :: nif_result(list(byte()))
+  @spec poseidon_single(binary()) :: nif_result(binary())
   def poseidon_single(_input), do: error()
 
-  @spec poseidon(list(byte()), list(byte())) :: nif_result(list(byte()))
+  @spec poseidon(binary(), binary()) :: nif_result(binary())
   def poseidon(_x, _y), do: error()
 
-  @spec poseidon_many(list(list(byte()))) :: nif_result(list(byte()))
+  @spec poseidon_many(list(binary())) :: nif_result(binary())
   def poseidon_many(_inputs), do: error()
 
-  @spec program_hash(list(byte())) :: nif_result(list(byte()))
+  @spec cairo_resource_npk(binary()) :: nif_result(binary())
+  def cairo_resource_npk(_nf_key), do: error()
+
+  @spec cairo_resource_psi(binary()) :: nif_result(binary())
+  def cairo_resource_psi(_resource), do: error()
+
+  @spec cairo_resource_rcm(binary()) :: nif_result(binary())
+  def cairo_resource_rcm(_resource), do: error()
+
+  @spec cairo_resource_commitment(binary()) :: nif_result(binary())
+  def cairo_resource_commitment(_resource), do: error()
+
+  @spec cairo_resource_commitment_as_output(binary(), binary()) ::
+          nif_result(binary())
+  def cairo_resource_commitment_as_output(_resource, _input_nullifier),
+    do: error()
+
+  @spec cairo_resource_nullifier(binary(), binary()) :: nif_result(binary())
+  def cairo_resource_nullifier(_resource, _nf_key), do: error()
+
+  @spec cairo_resource_kind(binary()) :: nif_result(binary())
+  def cairo_resource_kind(_resource), do: error()
+
+  @spec cairo_resource_delta(binary()) :: nif_result(binary())
+  def cairo_resource_delta(_resource), do: error()
+
+  @spec cairo_transaction_delta(
+          list(binary()),
+          list(binary()),
+          list(binary())
+        ) :: nif_result(binary())
+  def cairo_transaction_delta(_inputs, _outputs, _rcvs), do: error()
+
+  @spec cairo_ephemeral_resource(binary(), binary(), binary(), binary()) ::
+          nif_result({binary(), binary()})
+  def cairo_ephemeral_resource(_logic, _label, _quantity, _data),
+    do: error()
+
+  @spec cairo_eph_root() :: binary()
+  def cairo_eph_root(), do: error()
+
+  @spec cairo_dummy_merkle_path() :: list(binary())
+  def cairo_dummy_merkle_path(), do: error()
+
+  @spec cairo_merkle_tree_new() :: reference()
+  def cairo_merkle_tree_new(), do: error()
+
+  @spec cairo_merkle_tree_append(reference(), binary()) ::
+          nif_result(non_neg_integer())
+  def cairo_merkle_tree_append(_tree, _commitment), do: error()
+
+  @spec cairo_merkle_tree_root(reference()) :: binary()
+  def cairo_merkle_tree_root(_tree), do: error()
+
+  @spec cairo_merkle_tree_size(reference()) :: non_neg_integer()
+  def cairo_merkle_tree_size(_tree), do: error()
+
+  @spec cairo_merkle_tree_path(reference(), non_neg_integer()) ::
+          nif_result(list(binary()))
+  def cairo_merkle_tree_path(_tree, _position), do: error()
+
+  @spec cairo_merkle_tree_known_root(reference(), binary()) ::
+          nif_result(boolean())
+  def cairo_merkle_tree_known_root(_tree, _root), do: error()
+
+  @spec cairo_merkle_verify(
+          binary(),
+          binary(),
+          list(binary()),
+          non_neg_integer()
+        ) :: nif_result(boolean())
+  def cairo_merkle_verify(_root, _commitment, _path, _position),
+    do: error()
+
+  @spec cairo_merkle_frontier_new() :: binary()
+  def cairo_merkle_frontier_new(), do: error()
+
+  @spec cairo_merkle_frontier_append(binary(), binary()) ::
+          nif_result({binary(), non_neg_integer()})
+  def cairo_merkle_frontier_append(_frontier, _commitment), do: error()
+
+  @spec cairo_merkle_frontier_root(binary()) :: nif_result(binary())
+  def cairo_merkle_frontier_root(_frontier), do: error()
+
+  @spec cairo_merkle_frontier_size(binary()) ::
+          nif_result(non_neg_integer())
+  def cairo_merkle_frontier_size(_frontier), do: error()
+
+  @spec cairo_merkle_frontier_path(binary()) :: nif_result(list(binary()))
+  def cairo_merkle_frontier_path(_frontier), do: error()
+
+  @spec program_hash(binary()) :: nif_result(binary())
   def program_hash(_public_inputs), do: error()
 
+  @spec program_hash_from_json(binary()) :: nif_result(binary())
+  def program_hash_from_json(_program_content), do: error()
+
+  @spec cairo_felt_to_string(binary()) :: nif_result(binary())
   def cairo_felt_to_string(_felt), do: error()
 
-  def cairo_generate_compliance_input_json(
-        _input_resource,
-        _output_resource,
-        _path,
-        _position,
-        _input_nf_key,
-        _eph_root,
-        _rcv
-      ),
-      do: error()
+  @spec cairo_generate_compliance_input_json(Cairo.ComplianceInput.t()) ::
+          nif_result(binary())
+  def cairo_generate_compliance_input_json(_input), do: error()
+
+  @spec cairo_generate_compliance_units_json(list(map()), list(binary())) ::
+          nif_result(list({binary(), binary(), map()}))
+  def cairo_generate_compliance_units_json(_inputs, _outputs), do: error()
+
+  @spec cairo_parse_compliance_input_json(binary()) :: nif_result(map())
+  def cairo_parse_compliance_input_json(_json), do: error()
+
+  @spec cairo_encode_compliance_input_json(map()) :: nif_result(binary())
+  def cairo_encode_compliance_input_json(_input), do: error()
 
   defp error, do: :erlang.nif_error(:nif_not_loaded)
 end
@@ -81,7 +309,7 @@ defmodule Cairo.CairoVM do
   # When loading a NIF module, dummy clauses for all NIF function are required.
   # NIF dummies usually just error out when called when the NIF is not loaded, as that should never normally happen.
   @spec cairo_vm_runner(binary(), binary()) ::
-          nif_result({binary(), list(byte()), list(byte()), list(byte())})
+          nif_result({binary(), binary(), binary(), binary()})
   def cairo_vm_runner(_program_content, _program_inputs),
     do: :erlang.nif_error(:nif_not_loaded)
 end
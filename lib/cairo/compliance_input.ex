@@ -0,0 +1,58 @@
+defmodule Cairo.ComplianceInput do
+  @moduledoc """
+  The inputs of the compliance program, see
+  `Cairo.generate_compliance_input_json/1`.
+
+  `merkle_path` holds the 32 siblings of the commitment of the input
+  resource, from the leaf up, and `position` is the position of the
+  commitment in the tree, see `Cairo.merkle_tree_path/2`. The resources are
+  225 bytes, the other fields are felts.
+  """
+
+  use TypedStruct
+
+  alias Cairo.Bytes
+
+  typedstruct enforce: true do
+    field(:input_resource, Bytes.t())
+    field(:output_resource, Bytes.t())
+    field(:merkle_path, [Bytes.t()])
+    field(:position, non_neg_integer())
+    field(:input_nf_key, Bytes.t())
+    field(:eph_root, Bytes.t())
+    field(:rcv, Bytes.t())
+  end
+
+  @doc """
+  Builds the inputs from a map or a keyword list of their fields, unknown
+  fields are ignored.
+  """
+  @spec new(map() | keyword()) ::
+          {:ok, t()} | {:error, {:missing_fields, [atom()]}}
+  def new(fields) do
+    fields = Map.new(fields)
+
+    case @enforce_keys -- Map.keys(fields) do
+      [] -> {:ok, struct(__MODULE__, fields)}
+      missing -> {:error, {:missing_fields, missing}}
+    end
+  end
+
+  @doc """
+  Builds the inputs of a compliance unit consuming an ephemeral resource,
+  see `Cairo.ephemeral_resource/4`. Its Merkle path and root are the ones
+  the compliance program expects for ephemeral inputs.
+  """
+  @spec ephemeral(Bytes.t(), Bytes.t(), Bytes.t(), Bytes.t()) :: t()
+  def ephemeral(input_resource, input_nf_key, output_resource, rcv) do
+    %__MODULE__{
+      input_resource: input_resource,
+      output_resource: output_resource,
+      merkle_path: Cairo.dummy_merkle_path(),
+      position: 0,
+      input_nf_key: input_nf_key,
+      eph_root: Cairo.eph_root(),
+      rcv: rcv
+    }
+  end
+end
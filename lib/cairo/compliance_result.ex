@@ -0,0 +1,17 @@
+defmodule Cairo.ComplianceResult do
+  @moduledoc """
+  The output of the compliance program, see `Cairo.get_compliance_output/1`.
+  """
+
+  use TypedStruct
+
+  typedstruct enforce: true do
+    field(:input_nullifier, binary())
+    field(:output_cm, binary())
+    field(:root, binary())
+    field(:delta_x, binary())
+    field(:delta_y, binary())
+    field(:input_logic, binary())
+    field(:output_logic, binary())
+  end
+end
@@ -0,0 +1,12 @@
+defmodule Cairo.LogicResult do
+  @moduledoc """
+  The output of a resource logic program, see `Cairo.get_logic_output/1`.
+  """
+
+  use TypedStruct
+
+  typedstruct enforce: true do
+    field(:self_resource_id, binary())
+    field(:root, binary())
+  end
+end
@@ -0,0 +1,31 @@
+defmodule Cairo.ProofOptions do
+  @moduledoc """
+  Options used to generate and verify a Cairo proof.
+
+  Parameters left as `nil` are taken from the defaults of the chosen
+  security level. Custom parameters must still reach the conjectured
+  security of that level, and are rejected with the provable levels. The
+  `coset_offset` must be nonzero and not a root of unity. The resolved
+  options are recorded in the proof, and `Cairo.verify/3` rejects a proof
+  whose recorded options are weaker than the ones given, or than the
+  default ones when none are given.
+  """
+
+  use TypedStruct
+
+  @type security_level() ::
+          :conjecturable_80_bits
+          | :conjecturable_100_bits
+          | :conjecturable_128_bits
+          | :provable_80_bits
+          | :provable_100_bits
+          | :provable_128_bits
+
+  typedstruct do
+    field(:security_level, security_level(), default: :conjecturable_100_bits)
+    field(:blowup_factor, non_neg_integer() | nil)
+    field(:fri_number_of_queries, non_neg_integer() | nil)
+    field(:grinding_factor, non_neg_integer() | nil)
+    field(:coset_offset, non_neg_integer(), default: 3)
+  end
+end
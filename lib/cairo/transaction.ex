@@ -0,0 +1,21 @@
+defmodule Cairo.Transaction do
+  @moduledoc """
+  A transaction: the proofs of its compliance units and of the logics of
+  its resources, with its binding signature and the sorted distinct roots
+  of its compliance units. See `Cairo.create_transaction/3` and
+  `Cairo.verify_transaction/2`.
+
+  Proofs are `{proof, public_input}` tuples of binaries. The binding
+  signature signs the input nullifiers of the compliance units followed by
+  their output commitments.
+  """
+
+  use TypedStruct
+
+  typedstruct enforce: true do
+    field(:compliance_units, [{binary(), binary()}])
+    field(:logic_proofs, [{binary(), binary()}])
+    field(:binding_signature, binary())
+    field(:roots, [binary()])
+  end
+end
@@ -1,5 +1,73 @@
-use crate::utils::felt_to_string;
+use crate::{
+    errors::{ComplianceInputError, ResourceError},
+    merkle::{self, TREE_DEPTH},
+    resource::{self, Resource},
+    utils::{felt_from_bytes, felt_to_string, random_felt, BinaryBytes},
+};
+use num_bigint::BigUint;
+use rustler::{NifMap, NifStruct};
 use serde::{Deserialize, Serialize};
+use starknet_types_core::felt::Felt;
+use std::ops::Range;
+
+// The inputs of the compliance program, see compliance.juvix. The merkle
+// path holds the siblings of the input commitment from the leaf up, whether
+// they are on the left is given by the bits of its position.
+#[derive(NifStruct)]
+#[module = "Cairo.ComplianceInput"]
+pub struct ComplianceInput {
+    pub input_resource: BinaryBytes,
+    pub output_resource: BinaryBytes,
+    pub merkle_path: Vec<BinaryBytes>,
+    pub position: u64,
+    pub input_nf_key: BinaryBytes,
+    pub eph_root: BinaryBytes,
+    pub rcv: BinaryBytes,
+}
+
+// A resource consumed by a transaction, with the nf_key and the merkle path
+// of its commitment.
+#[derive(NifMap)]
+pub struct InputResource {
+    pub resource: BinaryBytes,
+    pub nf_key: BinaryBytes,
+    pub merkle_path: Vec<BinaryBytes>,
+    pub position: u64,
+}
+
+// The padding resources of a compliance unit, each with its nf_key. A
+// transaction needs logic proofs of them like of any other resource.
+#[derive(Default, NifMap)]
+pub struct Padding {
+    pub input: Option<(BinaryBytes, BinaryBytes)>,
+    pub output: Option<(BinaryBytes, BinaryBytes)>,
+}
+
+// A compliance input JSON as an Elixir map, with the felts as 32 bytes
+// big-endian binaries and the merkle path as {node, is_left} tuples. The
+// source is the JSON the fields were parsed from, if any.
+#[derive(NifMap)]
+pub struct ComplianceInputFields {
+    pub input: ResourceFields,
+    pub output: ResourceFields,
+    pub input_nf_key: BinaryBytes,
+    pub merkle_path: Vec<(BinaryBytes, bool)>,
+    pub rcv: BinaryBytes,
+    pub eph_root: BinaryBytes,
+    pub source: Option<String>,
+}
+
+#[derive(NifMap)]
+pub struct ResourceFields {
+    pub logic: BinaryBytes,
+    pub label: BinaryBytes,
+    pub quantity: BinaryBytes,
+    pub data: BinaryBytes,
+    pub eph: bool,
+    pub nonce: BinaryBytes,
+    pub npk: BinaryBytes,
+    pub rseed: BinaryBytes,
+}
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ComplianceInputJson {
@@ -29,82 +97,666 @@ struct PathNode {
     snd: bool,
 }
 
+impl ComplianceInput {
+    // Pair the inputs and the outputs of a transaction in compliance units,
+    // padding the shorter list with ephemeral resources. Every unit gets a
+    // fresh rcv, and comes with its padding resources.
+    pub fn units(inputs: &[InputResource], outputs: &[BinaryBytes]) -> Vec<(Self, Padding)> {
+        let units = inputs.len().max(outputs.len());
+        let eph_root: BinaryBytes = merkle::eph_root().to_bytes_be().to_vec().into();
+        (0..units)
+            .map(|i| {
+                let mut padding = Padding::default();
+                let (input_resource, input_nf_key, merkle_path, position) = match inputs.get(i) {
+                    Some(input) => (
+                        input.resource.clone(),
+                        input.nf_key.clone(),
+                        input.merkle_path.clone(),
+                        input.position,
+                    ),
+                    None => {
+                        let (resource, nf_key) = padding_resource();
+                        padding.input = Some((resource.clone(), nf_key.clone()));
+                        (resource, nf_key, dummy_merkle_path(), 0)
+                    }
+                };
+                let output_resource = match outputs.get(i) {
+                    Some(output) => output.clone(),
+                    None => {
+                        let (resource, nf_key) = padding_resource();
+                        padding.output = Some((resource.clone(), nf_key));
+                        resource
+                    }
+                };
+                let unit = Self {
+                    input_resource,
+                    output_resource,
+                    merkle_path,
+                    position,
+                    input_nf_key,
+                    eph_root: eph_root.clone(),
+                    rcv: random_felt().into(),
+                };
+                (unit, padding)
+            })
+            .collect()
+    }
+}
+
 impl ComplianceInputJson {
-    pub fn to_json_string(
-        input_resource: &Vec<u8>,
-        output_resource: &Vec<u8>,
-        path: &Vec<Vec<u8>>,
-        pos: u64,
-        input_nf_key: &Vec<u8>,
-        eph_root: &Vec<u8>,
-        rcv: &Vec<u8>,
-    ) -> String {
-        let input = ResourceJson::from_bytes(input_resource);
-        let output = ResourceJson::from_bytes(output_resource);
-
-        let rcv = felt_to_string(rcv);
-        let eph_root = felt_to_string(eph_root);
-        let input_nf_key = felt_to_string(input_nf_key);
-        let mut next_pos = pos;
-        let merkle_path = path
+    pub fn new(input: &ComplianceInput) -> Result<Self, ComplianceInputError> {
+        let input_resource = Resource::from_bytes(&input.input_resource.0)?;
+        let output_resource = Resource::from_bytes(&input.output_resource.0)?;
+
+        // The circuit derives the npk of the input from the nf_key, it has
+        // to be checked outside of it.
+        let input_nf_key = felt_from_bytes(&input.input_nf_key.0)?;
+        if resource::npk(&input_nf_key) != input_resource.npk {
+            return Err(ResourceError::InvalidNullifierKey.into());
+        }
+
+        if input.merkle_path.len() != TREE_DEPTH {
+            return Err(ComplianceInputError::InvalidPathLength(
+                input.merkle_path.len(),
+            ));
+        }
+        if input.position >> TREE_DEPTH != 0 {
+            return Err(ComplianceInputError::InvalidPosition(input.position));
+        }
+
+        // The bits of the position tell whether the nodes are on the left
+        let merkle_path = input
+            .merkle_path
             .iter()
-            .map(|v| {
-                let snd = if next_pos % 2 == 0 { false } else { true };
-                next_pos >>= 1;
-                PathNode {
-                    fst: felt_to_string(v),
-                    snd,
-                }
+            .enumerate()
+            .map(|(i, node)| {
+                Ok(PathNode {
+                    fst: felt_to_string(&node.0)?,
+                    snd: (input.position >> i) & 1 == 1,
+                })
             })
-            .collect();
+            .collect::<Result<_, ComplianceInputError>>()?;
 
-        let compliance_input = Self {
-            input,
-            output,
-            input_nf_key,
+        Ok(Self {
+            input: ResourceJson::from(&input_resource),
+            output: ResourceJson::from(&output_resource),
+            input_nf_key: input_nf_key.to_hex_string(),
             merkle_path,
-            rcv,
-            eph_root,
-        };
-        serde_json::to_string(&compliance_input).unwrap()
+            rcv: felt_to_string(&input.rcv.0)?,
+            eph_root: felt_to_string(&input.eph_root.0)?,
+        })
+    }
+
+    // Compact JSON with the keys in the order of the struct fields, the same
+    // JSON for all the inputs that parse to the same fields.
+    pub fn to_json_string(&self) -> Result<String, ComplianceInputError> {
+        serde_json::to_string(self).map_err(|e| ComplianceInputError::EncodingError(e.to_string()))
+    }
+
+    // The JSON written over source, the JSON the fields were parsed from:
+    // its whitespace, key order and other keys are kept, and so are the felt
+    // strings of unchanged values, so unchanged fields give back the source
+    // byte for byte. Without a source, or if the fields no longer fit it,
+    // e.g. with a longer merkle path, the compact JSON.
+    pub fn to_json_string_like(
+        &self,
+        source: Option<&str>,
+    ) -> Result<String, ComplianceInputError> {
+        match source.and_then(|source| self.rewrite(source)) {
+            Some(json) => Ok(json),
+            None => self.to_json_string(),
+        }
+    }
+
+    // Keys other than the fields are ignored.
+    pub fn from_json_str(json: &str) -> Result<Self, ComplianceInputError> {
+        serde_json::from_str(json).map_err(|e| ComplianceInputError::InvalidJson(e.to_string()))
+    }
+
+    // Felts are accepted in any hex form below the field prime, leading
+    // zeros included.
+    pub fn to_fields(&self) -> Result<ComplianceInputFields, ComplianceInputError> {
+        Ok(ComplianceInputFields {
+            input: self.input.to_fields()?,
+            output: self.output.to_fields()?,
+            input_nf_key: felt_bytes_from_hex(&self.input_nf_key)?,
+            merkle_path: self
+                .merkle_path
+                .iter()
+                .map(|node| Ok((felt_bytes_from_hex(&node.fst)?, node.snd)))
+                .collect::<Result<_, ComplianceInputError>>()?,
+            rcv: felt_bytes_from_hex(&self.rcv)?,
+            eph_root: felt_bytes_from_hex(&self.eph_root)?,
+            source: None,
+        })
+    }
+
+    pub fn from_fields(fields: &ComplianceInputFields) -> Result<Self, ComplianceInputError> {
+        Ok(Self {
+            input: ResourceJson::from_fields(&fields.input)?,
+            output: ResourceJson::from_fields(&fields.output)?,
+            input_nf_key: felt_to_string(&fields.input_nf_key.0)?,
+            merkle_path: fields
+                .merkle_path
+                .iter()
+                .map(|(node, is_left)| {
+                    Ok(PathNode {
+                        fst: felt_to_string(&node.0)?,
+                        snd: *is_left,
+                    })
+                })
+                .collect::<Result<_, ComplianceInputError>>()?,
+            rcv: felt_to_string(&fields.rcv.0)?,
+            eph_root: felt_to_string(&fields.eph_root.0)?,
+        })
+    }
+
+    // Each value of the source that is a field is replaced by the field,
+    // unless it is the same value. The source must hold every field once.
+    fn rewrite(&self, source: &str) -> Option<String> {
+        let mut written = vec![false; 19 + 2 * self.merkle_path.len()];
+        let mut json = String::with_capacity(source.len());
+        let mut end = 0;
+        for (path, range) in json_scalars(source)? {
+            if let [PathItem::Key(key), PathItem::Index(i), ..] = path.as_slice() {
+                if key == "merkle_path" && *i >= self.merkle_path.len() {
+                    return None;
+                }
+            }
+            let Some((slot, value)) = self.value_at(&path) else {
+                continue;
+            };
+            if std::mem::replace(&mut written[slot], true) {
+                return None;
+            }
+            let token = &source[range.clone()];
+            json.push_str(&source[end..range.start]);
+            if value.is_written(token) {
+                json.push_str(token);
+            } else {
+                json.push_str(&value.to_json());
+            }
+            end = range.end;
+        }
+        json.push_str(&source[end..]);
+        written.iter().all(|written| *written).then_some(json)
+    }
+
+    // The field at the path of a JSON value, with its index among the fields.
+    fn value_at(&self, path: &[PathItem]) -> Option<(usize, JsonScalar)> {
+        match path {
+            [PathItem::Key(key)] => match key.as_str() {
+                "input_nf_key" => Some((16, JsonScalar::Felt(&self.input_nf_key))),
+                "rcv" => Some((17, JsonScalar::Felt(&self.rcv))),
+                "eph_root" => Some((18, JsonScalar::Felt(&self.eph_root))),
+                _ => None,
+            },
+            [PathItem::Key(resource), PathItem::Key(key)] => {
+                let (offset, resource) = match resource.as_str() {
+                    "input" => (0, &self.input),
+                    "output" => (8, &self.output),
+                    _ => return None,
+                };
+                let (index, value) = resource.value_at(key)?;
+                Some((offset + index, value))
+            }
+            [PathItem::Key(path), PathItem::Index(i), PathItem::Key(key)]
+                if path == "merkle_path" =>
+            {
+                let node = self.merkle_path.get(*i)?;
+                match key.as_str() {
+                    "fst" => Some((19 + 2 * i, JsonScalar::Felt(&node.fst))),
+                    "snd" => Some((20 + 2 * i, JsonScalar::Bool(node.snd))),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl ResourceJson {
+    fn to_fields(&self) -> Result<ResourceFields, ComplianceInputError> {
+        Ok(ResourceFields {
+            logic: felt_bytes_from_hex(&self.logic)?,
+            label: felt_bytes_from_hex(&self.label)?,
+            quantity: felt_bytes_from_hex(&self.quantity)?,
+            data: felt_bytes_from_hex(&self.data)?,
+            eph: self.eph,
+            nonce: felt_bytes_from_hex(&self.nonce)?,
+            npk: felt_bytes_from_hex(&self.npk)?,
+            rseed: felt_bytes_from_hex(&self.rseed)?,
+        })
+    }
+
+    fn from_fields(fields: &ResourceFields) -> Result<Self, ComplianceInputError> {
+        Ok(Self {
+            logic: felt_to_string(&fields.logic.0)?,
+            label: felt_to_string(&fields.label.0)?,
+            quantity: felt_to_string(&fields.quantity.0)?,
+            data: felt_to_string(&fields.data.0)?,
+            eph: fields.eph,
+            nonce: felt_to_string(&fields.nonce.0)?,
+            npk: felt_to_string(&fields.npk.0)?,
+            rseed: felt_to_string(&fields.rseed.0)?,
+        })
     }
 }
 
 impl ResourceJson {
-    pub fn from_bytes(bytes: &Vec<u8>) -> Self {
+    fn value_at(&self, key: &str) -> Option<(usize, JsonScalar)> {
+        match key {
+            "logic" => Some((0, JsonScalar::Felt(&self.logic))),
+            "label" => Some((1, JsonScalar::Felt(&self.label))),
+            "quantity" => Some((2, JsonScalar::Felt(&self.quantity))),
+            "data" => Some((3, JsonScalar::Felt(&self.data))),
+            "eph" => Some((4, JsonScalar::Bool(self.eph))),
+            "nonce" => Some((5, JsonScalar::Felt(&self.nonce))),
+            "npk" => Some((6, JsonScalar::Felt(&self.npk))),
+            "rseed" => Some((7, JsonScalar::Felt(&self.rseed))),
+            _ => None,
+        }
+    }
+}
+
+impl From<&Resource> for ResourceJson {
+    fn from(resource: &Resource) -> Self {
         Self {
-            logic: felt_to_string(&bytes[0..32].to_vec()),
-            label: felt_to_string(&bytes[32..64].to_vec()),
-            quantity: felt_to_string(&bytes[64..96].to_vec()),
-            data: felt_to_string(&bytes[96..128].to_vec()),
-            nonce: felt_to_string(&bytes[128..160].to_vec()),
-            npk: felt_to_string(&bytes[160..192].to_vec()),
-            rseed: felt_to_string(&bytes[192..224].to_vec()),
-            eph: if bytes[224] == 0 { false } else { true },
+            logic: resource.logic.to_hex_string(),
+            label: resource.label.to_hex_string(),
+            quantity: resource.quantity.to_hex_string(),
+            data: resource.data.to_hex_string(),
+            eph: resource.eph,
+            nonce: resource.nonce.to_hex_string(),
+            npk: resource.npk.to_hex_string(),
+            rseed: resource.rseed.to_hex_string(),
         }
     }
 }
 
+// The path of ephemeral inputs, see merkle::dummy_path.
+pub fn dummy_merkle_path() -> Vec<BinaryBytes> {
+    merkle::dummy_path()
+        .iter()
+        .map(|node| node.to_bytes_be().to_vec().into())
+        .collect()
+}
+
+// A padding resource and its nf_key
+fn padding_resource() -> (BinaryBytes, BinaryBytes) {
+    let (resource, nf_key) = Resource::padding();
+    (
+        resource.to_bytes().into(),
+        nf_key.to_bytes_be().to_vec().into(),
+    )
+}
+
+fn felt_bytes_from_hex(hex: &str) -> Result<BinaryBytes, ComplianceInputError> {
+    felt_from_hex(hex)
+        .map(|felt| felt.to_bytes_be().to_vec().into())
+        .ok_or_else(|| ComplianceInputError::InvalidFeltString(hex.to_string()))
+}
+
+// A 0x prefixed hex felt below the field prime.
+fn felt_from_hex(hex: &str) -> Option<Felt> {
+    let digits = hex.strip_prefix("0x")?;
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = BigUint::parse_bytes(digits.as_bytes(), 16)?;
+    let bytes = value.to_bytes_be();
+    if bytes.len() > 32 {
+        return None;
+    }
+    let felt = Felt::from_bytes_be_slice(&bytes);
+    (BigUint::from_bytes_be(&felt.to_bytes_be()) == value).then_some(felt)
+}
+
+// A field value of a compliance input JSON, felts as their hex strings.
+enum JsonScalar<'a> {
+    Felt(&'a str),
+    Bool(bool),
+}
+
+impl JsonScalar<'_> {
+    fn to_json(&self) -> String {
+        match self {
+            JsonScalar::Felt(hex) => format!("\"{}\"", hex),
+            JsonScalar::Bool(value) => value.to_string(),
+        }
+    }
+
+    // Whether the JSON token is this value, felts in any hex form
+    fn is_written(&self, token: &str) -> bool {
+        match self {
+            JsonScalar::Felt(hex) => token
+                .strip_prefix('"')
+                .and_then(|token| token.strip_suffix('"'))
+                .and_then(felt_from_hex)
+                .is_some_and(|felt| felt_from_hex(hex) == Some(felt)),
+            JsonScalar::Bool(value) => token == value.to_string(),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum PathItem {
+    Key(String),
+    Index(usize),
+}
+
+// The scalar values of a JSON text, strings, numbers, booleans and nulls,
+// with their path of keys and array indices and their byte range. None if
+// the text is not JSON.
+fn json_scalars(json: &str) -> Option<Vec<(Vec<PathItem>, Range<usize>)>> {
+    let mut scanner = JsonScanner {
+        bytes: json.as_bytes(),
+        pos: 0,
+        path: Vec::new(),
+        scalars: Vec::new(),
+    };
+    scanner.value()?;
+    scanner.skip_whitespace();
+    (scanner.pos == json.len()).then_some(scanner.scalars)
+}
+
+struct JsonScanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    path: Vec<PathItem>,
+    scalars: Vec<(Vec<PathItem>, Range<usize>)>,
+}
+
+impl JsonScanner<'_> {
+    fn value(&mut self) -> Option<()> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.bytes.get(self.pos).copied()? {
+            b'{' => return self.object(),
+            b'[' => return self.array(),
+            b'"' => {
+                self.string()?;
+            }
+            _ => {
+                while matches!(
+                    self.bytes.get(self.pos),
+                    Some(byte) if byte.is_ascii_alphanumeric() || b"+-.".contains(byte)
+                ) {
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return None;
+                }
+            }
+        }
+        self.scalars.push((self.path.clone(), start..self.pos));
+        Some(())
+    }
+
+    fn object(&mut self) -> Option<()> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.eat(b'}') {
+            return Some(());
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            self.path.push(PathItem::Key(key));
+            self.value()?;
+            self.path.pop();
+            self.skip_whitespace();
+            if self.eat(b'}') {
+                return Some(());
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    fn array(&mut self) -> Option<()> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.eat(b']') {
+            return Some(());
+        }
+        let mut index = 0;
+        loop {
+            self.path.push(PathItem::Index(index));
+            self.value()?;
+            self.path.pop();
+            self.skip_whitespace();
+            if self.eat(b']') {
+                return Some(());
+            }
+            self.expect(b',')?;
+            index += 1;
+        }
+    }
+
+    // The content of a string as written, escapes included
+    fn string(&mut self) -> Option<String> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        loop {
+            match self.bytes.get(self.pos).copied()? {
+                b'"' => break,
+                b'\\' => self.pos += 2,
+                _ => self.pos += 1,
+            }
+        }
+        let content = std::str::from_utf8(&self.bytes[start..self.pos]).ok()?;
+        self.pos += 1;
+        Some(content.to_string())
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let eaten = self.bytes.get(self.pos) == Some(&byte);
+        if eaten {
+            self.pos += 1;
+        }
+        eaten
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.eat(byte).then_some(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.bytes.get(self.pos), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+fn test_compliance_input(input_resource: Vec<u8>) -> ComplianceInput {
+    ComplianceInput {
+        input_resource: input_resource.into(),
+        output_resource: vec![0u8; 225].into(),
+        merkle_path: (0..TREE_DEPTH).map(|_| random_felt().into()).collect(),
+        position: 5,
+        input_nf_key: Felt::ONE.to_bytes_be().to_vec().into(),
+        eph_root: random_felt().into(),
+        rcv: random_felt().into(),
+    }
+}
+
 #[test]
 fn test_compliance_input_json() {
-    use crate::utils::random_felt;
-    use rand::{thread_rng, RngCore};
-
-    let mut rng = thread_rng();
-    let mut random_resouce = [0u8; 225];
-    rng.fill_bytes(&mut random_resouce);
-
-    let path = (0..32).map(|_| random_felt()).collect();
-
-    let json = ComplianceInputJson::to_json_string(
-        &random_resouce.to_vec(),
-        &random_resouce.to_vec(),
-        &path,
-        0,
-        &random_felt(),
-        &random_felt(),
-        &random_felt(),
+    let mut input_resource = vec![0u8; 225];
+    input_resource[160..192].copy_from_slice(&resource::npk(&Felt::ONE).to_bytes_be());
+    let input = test_compliance_input(input_resource);
+
+    let json = ComplianceInputJson::new(&input).unwrap();
+    let snd: Vec<bool> = json.merkle_path.iter().map(|node| node.snd).collect();
+    assert_eq!(&snd[..4], &[true, false, true, false]);
+
+    // The JSON is parsed back to the same fields
+    let json_string = json.to_json_string().unwrap();
+    let fields = ComplianceInputJson::from_json_str(&json_string)
+        .and_then(|json| json.to_fields())
+        .unwrap();
+    assert_eq!(fields.input_nf_key.0, input.input_nf_key.0);
+    assert_eq!(fields.merkle_path[0].0 .0, input.merkle_path[0].0);
+    assert_eq!(
+        ComplianceInputJson::from_fields(&fields)
+            .and_then(|json| json.to_json_string())
+            .unwrap(),
+        json_string
     );
+    // Any hex felt below the field prime is accepted
+    assert_eq!(
+        felt_bytes_from_hex("0x01").unwrap().0,
+        Felt::ONE.to_bytes_be()
+    );
+    for invalid in [
+        "0x",
+        "1",
+        "0xg",
+        "0x800000000000011000000000000000000000000000000000000000000000001",
+    ] {
+        assert!(matches!(
+            felt_bytes_from_hex(invalid),
+            Err(ComplianceInputError::InvalidFeltString(_))
+        ));
+    }
+}
+
+#[test]
+fn test_compliance_input_json_round_trips() {
+    let file = include_str!("../../cairo_vm/compliance_input.json");
+    let fields = |json: &str| {
+        ComplianceInputJson::from_json_str(json)
+            .and_then(|json| json.to_fields())
+            .unwrap()
+    };
+    let encode = |fields: &ComplianceInputFields, source: Option<&str>| {
+        ComplianceInputJson::from_fields(fields)
+            .and_then(|json| json.to_json_string_like(source))
+            .unwrap()
+    };
+
+    // The stored input is pretty-printed, it is encoded back byte for byte
+    assert_eq!(encode(&fields(file), Some(file)), file);
+    let compact = encode(&fields(file), None);
+    assert_eq!(compact, file.split_whitespace().collect::<String>());
+
+    // Unknown keys and non-canonical felts are kept
+    let extended =
+        file.replacen('{', r#"{"note": "unused","#, 1)
+            .replacen(r#""0x12""#, r#""0x012""#, 1);
+    assert_eq!(encode(&fields(&extended), Some(&extended)), extended);
+
+    // A changed field is written in the formatting of the source
+    let mut changed = fields(file);
+    changed.rcv = Felt::TWO.to_bytes_be().to_vec().into();
+    let json = encode(&changed, Some(file));
+    assert_eq!(json.lines().count(), file.lines().count());
+    assert_eq!(fields(&json).rcv.0, Felt::TWO.to_bytes_be());
+    assert_eq!(fields(&json).input_nf_key.0, fields(file).input_nf_key.0);
 
-    println!("compliance_input_json: {}", json);
+    // Fields that do not fit the source give the compact JSON
+    let mut longer = fields(file);
+    longer.merkle_path.push(longer.merkle_path[0].clone());
+    assert!(encode(&longer, Some(file))
+        .split_whitespace()
+        .nth(1)
+        .is_none());
+}
+
+#[test]
+fn test_compliance_input_json_rejects_invalid_inputs() {
+    let mut resource = vec![0u8; 225];
+    resource[160..192].copy_from_slice(&resource::npk(&Felt::ONE).to_bytes_be());
+    let new = |input: &ComplianceInput| ComplianceInputJson::new(input).map(|_| ());
+
+    let input = test_compliance_input(resource[1..].to_vec());
+    assert!(matches!(
+        new(&input),
+        Err(ComplianceInputError::InvalidResource(
+            ResourceError::InvalidLength(224)
+        ))
+    ));
+
+    let mut invalid = resource.clone();
+    invalid[64..96].copy_from_slice(&[0xffu8; 32]);
+    assert!(matches!(
+        new(&test_compliance_input(invalid)),
+        Err(ComplianceInputError::InvalidResource(
+            ResourceError::InvalidFelt(_)
+        ))
+    ));
+
+    assert!(matches!(
+        new(&test_compliance_input(vec![0u8; 225])),
+        Err(ComplianceInputError::InvalidResource(
+            ResourceError::InvalidNullifierKey
+        ))
+    ));
+
+    let mut input = test_compliance_input(resource.clone());
+    input.merkle_path.pop();
+    assert!(matches!(
+        new(&input),
+        Err(ComplianceInputError::InvalidPathLength(31))
+    ));
+
+    let mut input = test_compliance_input(resource);
+    input.position = 1 << TREE_DEPTH;
+    assert!(matches!(
+        new(&input),
+        Err(ComplianceInputError::InvalidPosition(_))
+    ));
+}
+
+#[test]
+fn test_compliance_units_are_padded() {
+    let nf_key = Felt::ONE;
+    let mut resource = vec![0u8; 225];
+    resource[160..192].copy_from_slice(&resource::npk(&nf_key).to_bytes_be());
+    let input = InputResource {
+        resource: resource.clone().into(),
+        nf_key: nf_key.to_bytes_be().to_vec().into(),
+        merkle_path: dummy_merkle_path(),
+        position: 3,
+    };
+    let outputs: Vec<BinaryBytes> = (0..3).map(|_| resource.clone().into()).collect();
+
+    let units = ComplianceInput::units(&[input], &outputs);
+    assert_eq!(units.len(), 3);
+    assert_eq!(units[0].0.position, 3);
+    assert_eq!(units[0].0.input_resource.0, resource);
+    assert!(units[0].1.input.is_none() && units[0].1.output.is_none());
+    for (unit, padding) in &units[1..] {
+        let (padding_resource, nf_key) = padding.input.as_ref().unwrap();
+        assert_eq!(padding_resource.0, unit.input_resource.0);
+        assert_eq!(nf_key.0, unit.input_nf_key.0);
+        assert!(padding.output.is_none());
+
+        let padding = Resource::from_bytes(&unit.input_resource.0).unwrap();
+        assert!(padding.eph);
+        assert_eq!(padding.quantity, Felt::ZERO);
+        assert_eq!(padding.logic, *crate::TRIVIAL_RESOURCE_LOGIC_HASH);
+        assert_eq!(unit.eph_root.0, merkle::eph_root().to_bytes_be());
+        assert!(ComplianceInputJson::new(unit).is_ok());
+    }
+    assert_ne!(units[0].0.rcv.0, units[1].0.rcv.0);
+
+    // Padding outputs come with the nf_key their npk is derived from
+    let input = InputResource {
+        resource: resource.clone().into(),
+        nf_key: nf_key.to_bytes_be().to_vec().into(),
+        merkle_path: dummy_merkle_path(),
+        position: 0,
+    };
+    let units = ComplianceInput::units(&[input], &[]);
+    let (output, nf_key) = units[0].1.output.as_ref().unwrap();
+    assert_eq!(output.0, units[0].0.output_resource.0);
+    let output = Resource::from_bytes(&output.0).unwrap();
+    assert_eq!(
+        output.npk,
+        resource::npk(&felt_from_bytes(&nf_key.0).unwrap())
+    );
 }
@@ -0,0 +1,103 @@
+use crate::{errors::ProofEnvelopeError, options::CairoProofOptions};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+// Every encoded proof starts with the magic header followed by the format
+// version as a little-endian u16. The rest is the bincode encoded envelope.
+// Version 2 no longer embeds the public inputs, which are given to verify.
+pub const PROOF_MAGIC: [u8; 4] = *b"AARM";
+pub const PROOF_FORMAT_VERSION: u16 = 2;
+// The lambdaworks revision the proofs are generated with, the rev the
+// lambdaworks dependencies are pinned to in Cargo.toml. Proofs from another
+// prover version are rejected.
+pub const PROVER_VERSION: &str = "lambdaworks-c4fa1f21b98a56825c76b2c38108e3a7f79b3995";
+
+const HEADER_LEN: usize = PROOF_MAGIC.len() + 2;
+
+#[derive(Serialize, Deserialize)]
+pub struct ProofEnvelope<P> {
+    pub prover_version: String,
+    pub options: CairoProofOptions,
+    pub proof: P,
+}
+
+impl<P: Serialize + DeserializeOwned> ProofEnvelope<P> {
+    pub fn new(options: CairoProofOptions, proof: P) -> Self {
+        Self {
+            prover_version: PROVER_VERSION.to_string(),
+            options,
+            proof,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofEnvelopeError> {
+        let body = bincode::serde::encode_to_vec(self, bincode::config::standard())
+            .map_err(|e| ProofEnvelopeError::EncodingError(e.to_string()))?;
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + body.len());
+        bytes.extend_from_slice(&PROOF_MAGIC);
+        bytes.extend_from_slice(&PROOF_FORMAT_VERSION.to_le_bytes());
+        bytes.extend(body);
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofEnvelopeError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(ProofEnvelopeError::Truncated);
+        }
+        if bytes[0..PROOF_MAGIC.len()] != PROOF_MAGIC {
+            return Err(ProofEnvelopeError::InvalidMagic);
+        }
+        let format_version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if format_version != PROOF_FORMAT_VERSION {
+            return Err(ProofEnvelopeError::UnsupportedFormatVersion(format_version));
+        }
+
+        let (envelope, _): (Self, usize) =
+            bincode::serde::decode_from_slice(&bytes[HEADER_LEN..], bincode::config::standard())
+                .map_err(|e| ProofEnvelopeError::DecodingError(e.to_string()))?;
+        if envelope.prover_version != PROVER_VERSION {
+            return Err(ProofEnvelopeError::UnsupportedProverVersion(
+                envelope.prover_version,
+            ));
+        }
+
+        Ok(envelope)
+    }
+}
+
+#[test]
+fn test_envelope_header_checks() {
+    type Envelope = ProofEnvelope<Vec<u8>>;
+
+    assert!(matches!(
+        Envelope::from_bytes(&[0, 1, 2, 3]),
+        Err(ProofEnvelopeError::Truncated)
+    ));
+    assert!(matches!(
+        Envelope::from_bytes(b"CAIRO\x01\x00"),
+        Err(ProofEnvelopeError::InvalidMagic)
+    ));
+    assert!(matches!(
+        Envelope::from_bytes(b"AARM\x01\x00"),
+        Err(ProofEnvelopeError::UnsupportedFormatVersion(1))
+    ));
+}
+
+#[test]
+fn test_prover_version_is_the_pinned_revision() {
+    let revision = PROVER_VERSION.strip_prefix("lambdaworks-").unwrap();
+    let manifest = include_str!("../Cargo.toml");
+    let pinned = manifest
+        .lines()
+        .filter(|line| line.contains("github.com/lambdaclass/lambdaworks"))
+        .collect::<Vec<_>>();
+
+    assert!(!pinned.is_empty());
+    for line in pinned {
+        assert!(
+            line.contains(&format!("rev = \"{}\"", revision)),
+            "{}",
+            line
+        );
+    }
+}
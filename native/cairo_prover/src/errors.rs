@@ -1,12 +1,70 @@
-use rustler::{Encoder, Env, Term};
+use crate::{
+    envelope::{PROOF_FORMAT_VERSION, PROVER_VERSION},
+    public_input::SegmentType,
+    utils::BinaryBytes,
+};
+use rustler::{Encoder, Env, NifMap, NifUnitEnum, Term};
+
+// Errors reach Elixir as an atom naming the error, tagged with the details
+// when there are any, e.g. {:register_states_error, detail}.
+rustler::atoms! {
+    register_states_error,
+    cairo_memory_error,
+    proof_generation_error,
+    public_input_error,
+    encoding_error,
+    proof_options_error,
+    cancelled,
+    invalid_program_content,
+    invalid_input_json,
+    runtime_error,
+    proof_decoding_error,
+    public_input_decoding_error,
+    proof_options_mismatch,
+    unsupported_proof_version,
+    decoding_error,
+    segment_not_found,
+    address_not_found,
+    signature_generation_error,
+    input_error,
+    verification_error,
+    key_generation_error,
+    invalid_felt_length,
+    felt_out_of_range,
+    invalid_resource_length,
+    invalid_program_data,
+    invalid_json,
+    invalid_address,
+    unsupported_segment_type,
+    invalid_output_length,
+    invalid_nullifier_key,
+    point_at_infinity,
+    tree_full,
+    invalid_position,
+    invalid_frontier_length,
+    invalid_path_length,
+    invalid_felt_string,
+    invalid_compliance_proof,
+    invalid_logic_proof,
+    missing_logic_proof,
+    duplicate_resource,
+    unexpected_logic_proof,
+    logic_mismatch,
+    compliance_program_mismatch,
+    invalid_binding_signature,
+    roots_mismatch,
+    allocation_error,
+}
 
 #[derive(Debug)]
 pub(crate) enum CairoProveError {
     RegisterStatesError(String),
     CairoMemoryError(String),
     ProofGenerationError(String),
-    PublicInputError(String),
+    PublicInputError(PublicInputError),
     EncodingError(String),
+    ProofOptionsError(String),
+    Cancelled,
 }
 
 impl std::fmt::Display for CairoProveError {
@@ -19,15 +77,109 @@ impl std::fmt::Display for CairoProveError {
             CairoProveError::ProofGenerationError(msg) => {
                 write!(f, "Proof generation failed: {}", msg)
             }
-            CairoProveError::PublicInputError(msg) => write!(f, "Public input error: {}", msg),
+            CairoProveError::PublicInputError(e) => write!(f, "Public input error: {}", e),
             CairoProveError::EncodingError(msg) => write!(f, "Encoding error: {}", msg),
+            CairoProveError::ProofOptionsError(msg) => write!(f, "Proof options error: {}", msg),
+            CairoProveError::Cancelled => write!(f, "Proof job cancelled"),
         }
     }
 }
 
 impl Encoder for CairoProveError {
     fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        self.to_string().encode(env)
+        match self {
+            CairoProveError::RegisterStatesError(msg) => (register_states_error(), msg).encode(env),
+            CairoProveError::CairoMemoryError(msg) => (cairo_memory_error(), msg).encode(env),
+            CairoProveError::ProofGenerationError(msg) => {
+                (proof_generation_error(), msg).encode(env)
+            }
+            CairoProveError::PublicInputError(e) => (public_input_error(), e).encode(env),
+            CairoProveError::EncodingError(msg) => (encoding_error(), msg).encode(env),
+            CairoProveError::ProofOptionsError(msg) => (proof_options_error(), msg).encode(env),
+            CairoProveError::Cancelled => cancelled().encode(env),
+        }
+    }
+}
+
+// Where parsing the public input bytes failed, reported to Elixir as
+// %{offset: n, reason: atom}.
+#[derive(Debug, NifMap)]
+pub(crate) struct PublicInputError {
+    pub offset: usize,
+    pub reason: PublicInputErrorReason,
+}
+
+#[derive(Debug, Clone, Copy, NifUnitEnum)]
+pub(crate) enum PublicInputErrorReason {
+    TruncatedRcMin,
+    TruncatedRcMax,
+    TruncatedMemoryLength,
+    TruncatedMemoryAddress,
+    TruncatedMemoryValue,
+    InvalidMemoryValue,
+    TruncatedSegmentsLength,
+    TruncatedSegmentType,
+    TruncatedSegmentBegin,
+    TruncatedSegmentStop,
+    UnknownSegmentType,
+}
+
+impl std::fmt::Display for PublicInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?} at offset {}", self.reason, self.offset)
+    }
+}
+
+// The same errors as the CairoVM NIF reports, for the combined run and prove
+// entry point.
+#[derive(Debug)]
+pub(crate) enum CairoVMError {
+    InvalidProgramContent,
+    InvalidInputJSON,
+    RuntimeError(String),
+}
+
+impl std::fmt::Display for CairoVMError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CairoVMError::InvalidProgramContent => write!(f, "Invalid program content"),
+            CairoVMError::InvalidInputJSON => write!(f, "Invalid input JSON"),
+            CairoVMError::RuntimeError(msg) => write!(f, "Runtime error: {}", msg),
+        }
+    }
+}
+
+impl Encoder for CairoVMError {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        match self {
+            CairoVMError::InvalidProgramContent => invalid_program_content().encode(env),
+            CairoVMError::InvalidInputJSON => invalid_input_json().encode(env),
+            CairoVMError::RuntimeError(msg) => (runtime_error(), msg).encode(env),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum CairoRunAndProveError {
+    VMError(CairoVMError),
+    ProveError(CairoProveError),
+}
+
+impl std::fmt::Display for CairoRunAndProveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CairoRunAndProveError::VMError(e) => write!(f, "{}", e),
+            CairoRunAndProveError::ProveError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Encoder for CairoRunAndProveError {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        match self {
+            CairoRunAndProveError::VMError(e) => e.encode(env),
+            CairoRunAndProveError::ProveError(e) => e.encode(env),
+        }
     }
 }
 
@@ -35,6 +187,9 @@ impl Encoder for CairoProveError {
 pub(crate) enum CairoVerifyError {
     ProofDecodingError(String),
     PublicInputDecodingError(String),
+    ProofOptionsError(String),
+    ProofOptionsMismatch,
+    UnsupportedProofVersion(String),
 }
 
 impl std::fmt::Display for CairoVerifyError {
@@ -44,13 +199,74 @@ impl std::fmt::Display for CairoVerifyError {
             CairoVerifyError::PublicInputDecodingError(msg) => {
                 write!(f, "Public input decoding error: {}", msg)
             }
+            CairoVerifyError::ProofOptionsError(msg) => write!(f, "Proof options error: {}", msg),
+            CairoVerifyError::ProofOptionsMismatch => {
+                write!(
+                    f,
+                    "Proof options recorded in the proof are weaker than the expected ones"
+                )
+            }
+            CairoVerifyError::UnsupportedProofVersion(msg) => {
+                write!(f, "Unsupported proof version: {}", msg)
+            }
+        }
+    }
+}
+
+impl From<ProofEnvelopeError> for CairoVerifyError {
+    fn from(e: ProofEnvelopeError) -> Self {
+        match e {
+            ProofEnvelopeError::UnsupportedFormatVersion(_)
+            | ProofEnvelopeError::UnsupportedProverVersion(_) => {
+                CairoVerifyError::UnsupportedProofVersion(e.to_string())
+            }
+            _ => CairoVerifyError::ProofDecodingError(e.to_string()),
         }
     }
 }
 
 impl Encoder for CairoVerifyError {
     fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        self.to_string().encode(env)
+        match self {
+            CairoVerifyError::ProofDecodingError(msg) => (proof_decoding_error(), msg).encode(env),
+            CairoVerifyError::PublicInputDecodingError(msg) => {
+                (public_input_decoding_error(), msg).encode(env)
+            }
+            CairoVerifyError::ProofOptionsError(msg) => (proof_options_error(), msg).encode(env),
+            CairoVerifyError::ProofOptionsMismatch => proof_options_mismatch().encode(env),
+            CairoVerifyError::UnsupportedProofVersion(msg) => {
+                (unsupported_proof_version(), msg).encode(env)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ProofEnvelopeError {
+    Truncated,
+    InvalidMagic,
+    UnsupportedFormatVersion(u16),
+    UnsupportedProverVersion(String),
+    EncodingError(String),
+    DecodingError(String),
+}
+
+impl std::fmt::Display for ProofEnvelopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ProofEnvelopeError::Truncated => write!(f, "proof is too short for the header"),
+            ProofEnvelopeError::InvalidMagic => write!(f, "invalid proof magic header"),
+            ProofEnvelopeError::UnsupportedFormatVersion(version) => write!(
+                f,
+                "format version {}, expected {}",
+                version, PROOF_FORMAT_VERSION
+            ),
+            ProofEnvelopeError::UnsupportedProverVersion(version) => {
+                write!(f, "prover {}, expected {}", version, PROVER_VERSION)
+            }
+            ProofEnvelopeError::EncodingError(msg) => write!(f, "{}", msg),
+            ProofEnvelopeError::DecodingError(msg) => write!(f, "{}", msg),
+        }
     }
 }
 
@@ -59,6 +275,13 @@ pub(crate) enum CairoGetOutputError {
     DecodingError(String),
     SegmentNotFound,
     AddressNotFound(u64),
+    InvalidOutputLength(OutputLength),
+}
+
+#[derive(Debug, NifMap)]
+pub(crate) struct OutputLength {
+    pub expected: usize,
+    pub actual: usize,
 }
 
 impl std::fmt::Display for CairoGetOutputError {
@@ -71,13 +294,25 @@ impl std::fmt::Display for CairoGetOutputError {
             CairoGetOutputError::AddressNotFound(addr) => {
                 write!(f, "Address {} not found in public memory", addr)
             }
+            CairoGetOutputError::InvalidOutputLength(length) => write!(
+                f,
+                "Invalid output length: {} felts, expected {}",
+                length.actual, length.expected
+            ),
         }
     }
 }
 
 impl Encoder for CairoGetOutputError {
     fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        self.to_string().encode(env)
+        match self {
+            CairoGetOutputError::DecodingError(msg) => (decoding_error(), msg).encode(env),
+            CairoGetOutputError::SegmentNotFound => segment_not_found().encode(env),
+            CairoGetOutputError::AddressNotFound(addr) => (address_not_found(), addr).encode(env),
+            CairoGetOutputError::InvalidOutputLength(length) => {
+                (invalid_output_length(), length).encode(env)
+            }
+        }
     }
 }
 
@@ -98,7 +333,11 @@ impl std::fmt::Display for CairoSignError {
 
 impl Encoder for CairoSignError {
     fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        self.to_string().encode(env)
+        match self {
+            CairoSignError::SignatureGenerationError(msg) => {
+                (signature_generation_error(), msg).encode(env)
+            }
+        }
     }
 }
 
@@ -121,7 +360,10 @@ impl std::fmt::Display for CairoBindingSigVerifyError {
 
 impl Encoder for CairoBindingSigVerifyError {
     fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        self.to_string().encode(env)
+        match self {
+            CairoBindingSigVerifyError::InputError => input_error().encode(env),
+            CairoBindingSigVerifyError::VerificationError => verification_error().encode(env),
+        }
     }
 }
 
@@ -140,6 +382,438 @@ impl std::fmt::Display for CairoBindingSigError {
 
 impl Encoder for CairoBindingSigError {
     fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        self.to_string().encode(env)
+        match self {
+            CairoBindingSigError::KeyGenerationError => key_generation_error().encode(env),
+        }
+    }
+}
+
+// Felts are passed in as 32 big-endian bytes holding a value below the
+// field prime.
+#[derive(Debug)]
+pub(crate) enum FeltError {
+    InvalidLength(usize),
+    OutOfRange,
+}
+
+impl std::fmt::Display for FeltError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FeltError::InvalidLength(len) => {
+                write!(f, "Invalid felt length: {} bytes, expected 32", len)
+            }
+            FeltError::OutOfRange => write!(f, "Felt is not below the field prime"),
+        }
+    }
+}
+
+impl Encoder for FeltError {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        match self {
+            FeltError::InvalidLength(len) => (invalid_felt_length(), len).encode(env),
+            FeltError::OutOfRange => felt_out_of_range().encode(env),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum CairoProgramHashError {
+    DecodingError(String),
+    SegmentNotFound,
+    AddressNotFound(u64),
+    InvalidProgramContent,
+    InvalidProgramData(String),
+}
+
+impl std::fmt::Display for CairoProgramHashError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CairoProgramHashError::DecodingError(msg) => write!(f, "Decoding error: {}", msg),
+            CairoProgramHashError::SegmentNotFound => {
+                write!(f, "Program segment not found in memory segments")
+            }
+            CairoProgramHashError::AddressNotFound(addr) => {
+                write!(f, "Address {} not found in public memory", addr)
+            }
+            CairoProgramHashError::InvalidProgramContent => write!(f, "Invalid program content"),
+            CairoProgramHashError::InvalidProgramData(value) => {
+                write!(f, "Invalid program data: {}", value)
+            }
+        }
+    }
+}
+
+impl Encoder for CairoProgramHashError {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        match self {
+            CairoProgramHashError::DecodingError(msg) => (decoding_error(), msg).encode(env),
+            CairoProgramHashError::SegmentNotFound => segment_not_found().encode(env),
+            CairoProgramHashError::AddressNotFound(addr) => (address_not_found(), addr).encode(env),
+            CairoProgramHashError::InvalidProgramContent => invalid_program_content().encode(env),
+            CairoProgramHashError::InvalidProgramData(value) => {
+                (invalid_program_data(), value).encode(env)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ComplianceInputError {
+    InvalidResource(ResourceError),
+    InvalidFelt(FeltError),
+    InvalidPathLength(usize),
+    InvalidPosition(u64),
+    InvalidJson(String),
+    InvalidFeltString(String),
+    EncodingError(String),
+}
+
+impl std::fmt::Display for ComplianceInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ComplianceInputError::InvalidResource(e) => write!(f, "{}", e),
+            ComplianceInputError::InvalidFelt(e) => write!(f, "{}", e),
+            ComplianceInputError::InvalidPathLength(len) => {
+                write!(f, "Invalid merkle path length: {}", len)
+            }
+            ComplianceInputError::InvalidPosition(pos) => write!(f, "Invalid position: {}", pos),
+            ComplianceInputError::InvalidJson(msg) => write!(f, "Invalid JSON: {}", msg),
+            ComplianceInputError::InvalidFeltString(felt) => write!(f, "Invalid felt: {}", felt),
+            ComplianceInputError::EncodingError(msg) => write!(f, "Encoding error: {}", msg),
+        }
+    }
+}
+
+impl From<FeltError> for ComplianceInputError {
+    fn from(e: FeltError) -> Self {
+        ComplianceInputError::InvalidFelt(e)
+    }
+}
+
+impl From<ResourceError> for ComplianceInputError {
+    fn from(e: ResourceError) -> Self {
+        ComplianceInputError::InvalidResource(e)
+    }
+}
+
+impl Encoder for ComplianceInputError {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        match self {
+            ComplianceInputError::InvalidResource(e) => e.encode(env),
+            ComplianceInputError::InvalidFelt(e) => e.encode(env),
+            ComplianceInputError::InvalidPathLength(len) => {
+                (invalid_path_length(), len).encode(env)
+            }
+            ComplianceInputError::InvalidPosition(pos) => (invalid_position(), pos).encode(env),
+            ComplianceInputError::InvalidJson(msg) => (invalid_json(), msg).encode(env),
+            ComplianceInputError::InvalidFeltString(felt) => {
+                (invalid_felt_string(), felt).encode(env)
+            }
+            ComplianceInputError::EncodingError(msg) => (encoding_error(), msg).encode(env),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum PublicInputJsonError {
+    PublicInputError(PublicInputError),
+    DecodingError(String),
+    EncodingError(String),
+    InvalidJson(String),
+    InvalidAddress(String),
+    UnsupportedSegmentType(SegmentType),
+}
+
+impl std::fmt::Display for PublicInputJsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PublicInputJsonError::PublicInputError(e) => write!(f, "Public input error: {}", e),
+            PublicInputJsonError::DecodingError(msg) => write!(f, "Decoding error: {}", msg),
+            PublicInputJsonError::EncodingError(msg) => write!(f, "Encoding error: {}", msg),
+            PublicInputJsonError::InvalidJson(msg) => write!(f, "Invalid JSON: {}", msg),
+            PublicInputJsonError::InvalidAddress(addr) => {
+                write!(f, "Address {} does not fit in 64 bits", addr)
+            }
+            PublicInputJsonError::UnsupportedSegmentType(segment_type) => {
+                write!(
+                    f,
+                    "Segment type {:?} is not supported by the prover",
+                    segment_type
+                )
+            }
+        }
+    }
+}
+
+impl Encoder for PublicInputJsonError {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        match self {
+            PublicInputJsonError::PublicInputError(e) => (public_input_error(), e).encode(env),
+            PublicInputJsonError::DecodingError(msg) => (decoding_error(), msg).encode(env),
+            PublicInputJsonError::EncodingError(msg) => (encoding_error(), msg).encode(env),
+            PublicInputJsonError::InvalidJson(msg) => (invalid_json(), msg).encode(env),
+            PublicInputJsonError::InvalidAddress(addr) => (invalid_address(), addr).encode(env),
+            PublicInputJsonError::UnsupportedSegmentType(segment_type) => {
+                (unsupported_segment_type(), segment_type.to_string()).encode(env)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ResourceError {
+    InvalidLength(usize),
+    InvalidFelt(FeltError),
+    InvalidNullifierKey,
+}
+
+impl std::fmt::Display for ResourceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ResourceError::InvalidLength(len) => {
+                write!(f, "Invalid resource length: {} bytes, expected 225", len)
+            }
+            ResourceError::InvalidFelt(e) => write!(f, "{}", e),
+            ResourceError::InvalidNullifierKey => {
+                write!(f, "The nullifier key does not match the resource npk")
+            }
+        }
+    }
+}
+
+impl From<FeltError> for ResourceError {
+    fn from(e: FeltError) -> Self {
+        ResourceError::InvalidFelt(e)
+    }
+}
+
+impl Encoder for ResourceError {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        match self {
+            ResourceError::InvalidLength(len) => (invalid_resource_length(), len).encode(env),
+            ResourceError::InvalidFelt(e) => e.encode(env),
+            ResourceError::InvalidNullifierKey => invalid_nullifier_key().encode(env),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum DeltaError {
+    InvalidResource(ResourceError),
+    InvalidFelt(FeltError),
+    PointAtInfinity,
+}
+
+impl std::fmt::Display for DeltaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DeltaError::InvalidResource(e) => write!(f, "{}", e),
+            DeltaError::InvalidFelt(e) => write!(f, "{}", e),
+            DeltaError::PointAtInfinity => write!(f, "The delta is the point at infinity"),
+        }
+    }
+}
+
+impl From<ResourceError> for DeltaError {
+    fn from(e: ResourceError) -> Self {
+        DeltaError::InvalidResource(e)
+    }
+}
+
+impl From<FeltError> for DeltaError {
+    fn from(e: FeltError) -> Self {
+        DeltaError::InvalidFelt(e)
+    }
+}
+
+impl Encoder for DeltaError {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        match self {
+            DeltaError::InvalidResource(e) => e.encode(env),
+            DeltaError::InvalidFelt(e) => e.encode(env),
+            DeltaError::PointAtInfinity => point_at_infinity().encode(env),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum MerkleTreeError {
+    TreeFull,
+    InvalidPosition(u64),
+    InvalidFrontierLength(usize),
+    InvalidFelt(FeltError),
+}
+
+impl std::fmt::Display for MerkleTreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MerkleTreeError::TreeFull => write!(f, "The commitment tree is full"),
+            MerkleTreeError::InvalidPosition(pos) => write!(f, "Invalid leaf position: {}", pos),
+            MerkleTreeError::InvalidFrontierLength(len) => {
+                write!(f, "Invalid frontier length: {}", len)
+            }
+            MerkleTreeError::InvalidFelt(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<FeltError> for MerkleTreeError {
+    fn from(e: FeltError) -> Self {
+        MerkleTreeError::InvalidFelt(e)
+    }
+}
+
+impl Encoder for MerkleTreeError {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        match self {
+            MerkleTreeError::TreeFull => tree_full().encode(env),
+            MerkleTreeError::InvalidPosition(pos) => (invalid_position(), pos).encode(env),
+            MerkleTreeError::InvalidFrontierLength(len) => {
+                (invalid_frontier_length(), len).encode(env)
+            }
+            MerkleTreeError::InvalidFelt(e) => e.encode(env),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum TransactionError {
+    InvalidComplianceProof(usize, Option<CairoVerifyError>),
+    InvalidLogicProof(usize, Option<CairoVerifyError>),
+    OutputError(CairoGetOutputError),
+    ProgramHashError(CairoProgramHashError),
+    InvalidFelt(FeltError),
+    MissingLogicProof(Vec<u8>),
+    DuplicateResource(Vec<u8>),
+    UnexpectedLogicProof(usize),
+    LogicMismatch(usize),
+    ComplianceProgramMismatch(usize),
+    InvalidBindingSignature,
+    BindingSignatureError(CairoBindingSigVerifyError),
+    SignError(CairoSignError),
+    RootsMismatch,
+    EncodingError(String),
+    DecodingError(String),
+}
+
+impl std::fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TransactionError::InvalidComplianceProof(index, None) => {
+                write!(f, "Invalid compliance proof {}", index)
+            }
+            TransactionError::InvalidComplianceProof(index, Some(e)) => {
+                write!(f, "Invalid compliance proof {}: {}", index, e)
+            }
+            TransactionError::InvalidLogicProof(index, None) => {
+                write!(f, "Invalid logic proof {}", index)
+            }
+            TransactionError::InvalidLogicProof(index, Some(e)) => {
+                write!(f, "Invalid logic proof {}: {}", index, e)
+            }
+            TransactionError::OutputError(e) => write!(f, "{}", e),
+            TransactionError::ProgramHashError(e) => write!(f, "{}", e),
+            TransactionError::InvalidFelt(e) => write!(f, "{}", e),
+            TransactionError::MissingLogicProof(id) => {
+                write!(f, "No logic proof for the resource {:?}", id)
+            }
+            TransactionError::DuplicateResource(id) => {
+                write!(f, "The resource {:?} appears twice", id)
+            }
+            TransactionError::UnexpectedLogicProof(index) => {
+                write!(f, "The logic proof {} proves no resource", index)
+            }
+            TransactionError::LogicMismatch(index) => {
+                write!(f, "The logic proof {} is not of the resource logic", index)
+            }
+            TransactionError::ComplianceProgramMismatch(index) => {
+                write!(
+                    f,
+                    "The compliance unit {} is not of the compliance program",
+                    index
+                )
+            }
+            TransactionError::InvalidBindingSignature => write!(f, "Invalid binding signature"),
+            TransactionError::BindingSignatureError(e) => write!(f, "{}", e),
+            TransactionError::SignError(e) => write!(f, "{}", e),
+            TransactionError::RootsMismatch => {
+                write!(f, "The roots are not the ones of the compliance units")
+            }
+            TransactionError::EncodingError(msg) => write!(f, "Encoding error: {}", msg),
+            TransactionError::DecodingError(msg) => write!(f, "Decoding error: {}", msg),
+        }
+    }
+}
+
+impl From<CairoGetOutputError> for TransactionError {
+    fn from(e: CairoGetOutputError) -> Self {
+        TransactionError::OutputError(e)
+    }
+}
+
+impl From<CairoProgramHashError> for TransactionError {
+    fn from(e: CairoProgramHashError) -> Self {
+        TransactionError::ProgramHashError(e)
+    }
+}
+
+impl From<FeltError> for TransactionError {
+    fn from(e: FeltError) -> Self {
+        TransactionError::InvalidFelt(e)
+    }
+}
+
+impl From<CairoBindingSigVerifyError> for TransactionError {
+    fn from(e: CairoBindingSigVerifyError) -> Self {
+        TransactionError::BindingSignatureError(e)
+    }
+}
+
+impl From<CairoSignError> for TransactionError {
+    fn from(e: CairoSignError) -> Self {
+        TransactionError::SignError(e)
+    }
+}
+
+// Proofs that do not verify are tagged with their index, the ones that cannot
+// be decoded with their index and the decoding error.
+impl Encoder for TransactionError {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        match self {
+            TransactionError::InvalidComplianceProof(index, None) => {
+                (invalid_compliance_proof(), index).encode(env)
+            }
+            TransactionError::InvalidComplianceProof(index, Some(e)) => {
+                (invalid_compliance_proof(), index, e).encode(env)
+            }
+            TransactionError::InvalidLogicProof(index, None) => {
+                (invalid_logic_proof(), index).encode(env)
+            }
+            TransactionError::InvalidLogicProof(index, Some(e)) => {
+                (invalid_logic_proof(), index, e).encode(env)
+            }
+            TransactionError::OutputError(e) => e.encode(env),
+            TransactionError::ProgramHashError(e) => e.encode(env),
+            TransactionError::InvalidFelt(e) => e.encode(env),
+            TransactionError::MissingLogicProof(id) => {
+                (missing_logic_proof(), BinaryBytes(id.clone())).encode(env)
+            }
+            TransactionError::DuplicateResource(id) => {
+                (duplicate_resource(), BinaryBytes(id.clone())).encode(env)
+            }
+            TransactionError::UnexpectedLogicProof(index) => {
+                (unexpected_logic_proof(), index).encode(env)
+            }
+            TransactionError::LogicMismatch(index) => (logic_mismatch(), index).encode(env),
+            TransactionError::ComplianceProgramMismatch(index) => {
+                (compliance_program_mismatch(), index).encode(env)
+            }
+            TransactionError::InvalidBindingSignature => invalid_binding_signature().encode(env),
+            TransactionError::BindingSignatureError(e) => e.encode(env),
+            TransactionError::SignError(e) => e.encode(env),
+            TransactionError::RootsMismatch => roots_mismatch().encode(env),
+            TransactionError::EncodingError(msg) => (encoding_error(), msg).encode(env),
+            TransactionError::DecodingError(msg) => (decoding_error(), msg).encode(env),
+        }
     }
 }
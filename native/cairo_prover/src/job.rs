@@ -0,0 +1,83 @@
+use crate::{errors::CairoProveError, utils::BinaryBytes};
+use lazy_static::lazy_static;
+use rustler::{Encoder, LocalPid, OwnedEnv, ResourceArc};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+rustler::atoms! {
+    cairo_proof,
+}
+
+const RUNNING: u8 = 0;
+const DONE: u8 = 1;
+const CANCELLED: u8 = 2;
+
+lazy_static! {
+    // Async proofs run on their own pool so that they don't hold on to the
+    // dirty schedulers.
+    static ref PROVER_POOL: rayon::ThreadPool = rayon::ThreadPoolBuilder::new()
+        .thread_name(|i| format!("cairo-prover-{}", i))
+        .build()
+        .expect("Failed to build the prover thread pool");
+}
+
+type ProveResult = Result<(Vec<u8>, Vec<u8>), CairoProveError>;
+
+pub struct ProveJob {
+    state: AtomicU8,
+}
+
+impl ProveJob {
+    // Run prove on the prover pool and send {:cairo_proof, job, result} to
+    // pid when it is done. A cancelled job stops at the next stage of the
+    // prover and reports the Cancelled error: cancellation is only checked
+    // between stages, a stage that is running is not interrupted.
+    pub fn spawn<F>(pid: LocalPid, prove: F) -> ResourceArc<ProveJob>
+    where
+        F: FnOnce(&dyn Fn() -> bool) -> ProveResult + Send + 'static,
+    {
+        let job = ResourceArc::new(ProveJob {
+            state: AtomicU8::new(RUNNING),
+        });
+
+        let worker_job = job.clone();
+        PROVER_POOL.spawn(move || {
+            let result = if worker_job.is_cancelled() {
+                Err(CairoProveError::Cancelled)
+            } else {
+                prove(&|| worker_job.is_cancelled())
+            };
+
+            // A job cancelled after the last stage still reports the
+            // cancellation, the caller was told that it took effect.
+            let result = if worker_job.finish() {
+                result.map(|(proof, public_input)| (BinaryBytes(proof), BinaryBytes(public_input)))
+            } else {
+                Err(CairoProveError::Cancelled)
+            };
+
+            let mut msg_env = OwnedEnv::new();
+            msg_env.send_and_clear(&pid, |env| {
+                (cairo_proof(), worker_job.clone(), result).encode(env)
+            });
+        });
+
+        job
+    }
+
+    // Returns true if the job was still running.
+    pub fn cancel(&self) -> bool {
+        self.state
+            .compare_exchange(RUNNING, CANCELLED, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.state.load(Ordering::SeqCst) == CANCELLED
+    }
+
+    fn finish(&self) -> bool {
+        self.state
+            .compare_exchange(RUNNING, DONE, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
+}
@@ -1,14 +1,35 @@
 mod compliance_input;
+mod envelope;
 mod errors;
+mod job;
+mod merkle;
+mod options;
+mod outputs;
+mod public_input;
+mod resource;
+mod schnorr;
+mod transaction;
 mod utils;
 
 use crate::{
-    compliance_input::ComplianceInputJson,
+    compliance_input::{
+        ComplianceInput, ComplianceInputFields, ComplianceInputJson, InputResource, Padding,
+    },
+    envelope::ProofEnvelope,
     errors::{
-        CairoBindingSigError, CairoBindingSigVerifyError, CairoGetOutputError, CairoProveError,
-        CairoSignError, CairoVerifyError,
+        CairoBindingSigError, CairoBindingSigVerifyError, CairoGetOutputError,
+        CairoProgramHashError, CairoProveError, CairoRunAndProveError, CairoSignError,
+        CairoVMError, CairoVerifyError, ComplianceInputError, DeltaError, FeltError,
+        MerkleTreeError, PublicInputError, PublicInputJsonError,
     },
-    utils::{felt_to_string, random_felt},
+    job::ProveJob,
+    merkle::{MerkleFrontier, MerkleTree, MerkleTreeResource},
+    options::CairoProofOptions,
+    outputs::{ComplianceResult, LogicResult},
+    public_input::{ProverPublicInput, VmPublicInput},
+    resource::Resource,
+    transaction::{ProofRecord, Transaction},
+    utils::{felt_from_bytes, felt_to_string, felts_from_bytes, random_felt, BinaryBytes},
 };
 use cairo_platinum_prover::{
     air::{generate_cairo_proof, verify_cairo_proof, PublicInputs, Segment, SegmentName},
@@ -18,45 +39,131 @@ use cairo_platinum_prover::{
     Felt252,
 };
 use hashbrown::HashMap;
+use juvix_cairo_vm::{anoma_cairo_vm_runner, program_input::ProgramInput};
 use lambdaworks_math::traits::ByteConversion;
 use num_bigint::BigInt;
 use num_integer::Integer;
 use num_traits::Zero;
-use rand::{thread_rng, RngCore};
-use rustler::{Error, NifResult};
-use stark_platinum_prover::proof::options::{ProofOptions, SecurityLevel};
-use starknet_crypto::{poseidon_hash, poseidon_hash_many, poseidon_hash_single, sign, verify};
+use rayon::prelude::*;
+use rustler::{Atom, Binary, Env, Error, LocalPid, NifResult, NifUnitEnum, ResourceArc, Term};
+use starknet_crypto::{
+    poseidon_hash, poseidon_hash_many, poseidon_hash_single, recover, rfc6979_generate_k, sign,
+    verify, ExtendedSignature, SignError,
+};
 use starknet_curve::curve_params::{EC_ORDER, GENERATOR};
 use starknet_types_core::{
     curve::{AffinePoint, ProjectivePoint},
     felt::Felt,
 };
-use std::ops::Add;
+use std::ops::{Add, Deref};
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn cairo_prove<'a>(
+    trace: Binary<'a>,
+    memory: Binary<'a>,
+    public_input: Binary<'a>,
+    options: CairoProofOptions,
+) -> NifResult<(BinaryBytes, BinaryBytes)> {
+    let (proof, public_input) = prove(&trace, &memory, &public_input, &options, || false)
+        .map_err(|e| Error::Term(Box::new(e)))?;
+
+    Ok((proof.into(), public_input.into()))
+}
 
+// Start proving on the prover thread pool and return the job right away. The
+// result is sent to pid as {:cairo_proof, job, result} once the job is done.
+// Copying the trace and memory takes milliseconds for large programs, so it
+// runs on a dirty scheduler.
 #[rustler::nif(schedule = "DirtyCpu")]
-fn cairo_prove(
-    trace: Vec<u8>,
-    memory: Vec<u8>,
-    public_input: Vec<u8>,
-) -> NifResult<(Vec<u8>, Vec<u8>)> {
+fn cairo_prove_async<'a>(
+    trace: Binary<'a>,
+    memory: Binary<'a>,
+    public_input: Binary<'a>,
+    options: CairoProofOptions,
+    pid: LocalPid,
+) -> ResourceArc<ProveJob> {
+    // The job outlives the call, so it gets its own copy of the inputs
+    let trace = trace.as_slice().to_vec();
+    let memory = memory.as_slice().to_vec();
+    let public_input = public_input.as_slice().to_vec();
+
+    ProveJob::spawn(pid, move |is_cancelled| {
+        prove(&trace, &memory, &public_input, &options, is_cancelled)
+    })
+}
+
+// Cancel a proving job. Returns false if the job was already done or
+// cancelled. The job only stops between the stages of prove, so a job in
+// generate_cairo_proof runs until the proof is done.
+#[rustler::nif]
+fn cairo_prove_cancel(job: ResourceArc<ProveJob>) -> bool {
+    job.cancel()
+}
+
+// Run the program in the VM and prove the execution in one call. The trace and
+// memory stay in native memory, only the program output, the proof and the
+// public inputs are returned.
+#[rustler::nif(schedule = "DirtyCpu")]
+fn cairo_run_and_prove(
+    program_content: String,
+    inputs: String,
+    options: CairoProofOptions,
+) -> NifResult<(String, BinaryBytes, BinaryBytes)> {
+    let (output, trace, memory, vm_public_input) = run_program(&program_content, &inputs)
+        .map_err(|e| Error::Term(Box::new(CairoRunAndProveError::VMError(e))))?;
+
+    let (proof, public_input) = prove(&trace, &memory, &vm_public_input, &options, || false)
+        .map_err(|e| Error::Term(Box::new(CairoRunAndProveError::ProveError(e))))?;
+
+    Ok((output, proof.into(), public_input.into()))
+}
+
+// The same steps as the cairo_vm_runner NIF of the CairoVM crate.
+fn run_program(
+    program_content: &str,
+    inputs: &str,
+) -> Result<(String, Vec<u8>, Vec<u8>, Vec<u8>), CairoVMError> {
+    // Validate program content
+    serde_json::from_str::<serde_json::Value>(program_content)
+        .map_err(|_| CairoVMError::InvalidProgramContent)?;
+
+    // Load program input
+    let program_input = if inputs.is_empty() {
+        ProgramInput::new(std::collections::HashMap::new())
+    } else {
+        ProgramInput::from_json(inputs).map_err(|_| CairoVMError::InvalidInputJSON)?
+    };
+
+    anoma_cairo_vm_runner(program_content.as_bytes(), program_input)
+        .map_err(|e| CairoVMError::RuntimeError(e.to_string()))
+}
+
+// Generate the proof and encode it along with the public inputs. The
+// is_cancelled check is done between the proving stages, so that an async
+// job can stop early.
+fn prove(
+    trace: &[u8],
+    memory: &[u8],
+    public_input: &[u8],
+    options: &CairoProofOptions,
+    is_cancelled: impl Fn() -> bool,
+) -> Result<(Vec<u8>, Vec<u8>), CairoProveError> {
+    // Resolve the proof options before doing any work
+    let options = options
+        .resolve()
+        .map_err(CairoProveError::ProofOptionsError)?;
+    let proof_options = options.to_proof_options();
+
     // Generating the prover args
-    let register_states = RegisterStates::from_bytes_le(&trace).map_err(|e| {
-        Error::Term(Box::new(CairoProveError::RegisterStatesError(format!(
-            "{:?}",
-            e
-        ))))
-    })?;
-
-    let memory = CairoMemory::from_bytes_le(&memory).map_err(|e| {
-        Error::Term(Box::new(CairoProveError::CairoMemoryError(format!(
-            "{:?}",
-            e
-        ))))
-    })?;
+    let register_states = RegisterStates::from_bytes_le(trace)
+        .map_err(|e| CairoProveError::RegisterStatesError(format!("{:?}", e)))?;
+
+    let memory = CairoMemory::from_bytes_le(memory)
+        .map_err(|e| CairoProveError::CairoMemoryError(format!("{:?}", e)))?;
 
     // Handle public inputs
-    let (rc_min, rc_max, public_memory, memory_segments) = parse_public_input(&public_input)
-        .map_err(|e| Error::Term(Box::new(CairoProveError::PublicInputError(e.to_string()))))?;
+    let (rc_min, rc_max, public_memory, memory_segments) =
+        parse_public_input(public_input).map_err(CairoProveError::PublicInputError)?;
 
     let num_steps = register_states.steps();
     let mut pub_inputs = PublicInputs {
@@ -71,26 +178,29 @@ fn cairo_prove(
         public_memory,
         num_steps,
     };
+    if is_cancelled() {
+        return Err(CairoProveError::Cancelled);
+    }
 
     // Build main trace
     let main_trace = build_main_trace(&register_states, &memory, &mut pub_inputs);
+    if is_cancelled() {
+        return Err(CairoProveError::Cancelled);
+    }
 
     // Generating proof
-    let proof_options = ProofOptions::new_secure(SecurityLevel::Conjecturable100Bits, 3);
-    let proof = generate_cairo_proof(&main_trace, &pub_inputs, &proof_options).map_err(|e| {
-        Error::Term(Box::new(CairoProveError::ProofGenerationError(format!(
-            "{:?}",
-            e
-        ))))
-    })?;
-
-    // Encode proof and pub_inputs
-    let proof_bytes = bincode::serde::encode_to_vec(proof, bincode::config::standard())
-        .map_err(|e| Error::Term(Box::new(CairoProveError::EncodingError(format!("{:?}", e)))))?;
+    let proof = generate_cairo_proof(&main_trace, &pub_inputs, &proof_options)
+        .map_err(|e| CairoProveError::ProofGenerationError(format!("{:?}", e)))?;
+    if is_cancelled() {
+        return Err(CairoProveError::Cancelled);
+    }
+
+    // Encode pub_inputs, and the proof in a versioned envelope
     let pub_input_bytes = bincode::serde::encode_to_vec(&pub_inputs, bincode::config::standard())
-        .map_err(|e| {
-        Error::Term(Box::new(CairoProveError::EncodingError(format!("{:?}", e))))
-    })?;
+        .map_err(|e| CairoProveError::EncodingError(format!("{:?}", e)))?;
+    let proof_bytes = ProofEnvelope::new(options, proof)
+        .to_bytes()
+        .map_err(|e| CairoProveError::EncodingError(e.to_string()))?;
 
     Ok((proof_bytes, pub_input_bytes))
 }
@@ -104,131 +214,160 @@ fn parse_public_input(
         HashMap<Felt252, Felt252>,
         HashMap<SegmentName, Segment>,
     ),
-    &'static str,
+    PublicInputError,
 > {
-    let rc_min = u16::from_le_bytes(
-        public_input
-            .get(0..2)
-            .ok_or("Input must be at least 2 bytes long for rc_min")?
-            .try_into()
-            .map_err(|_| "Failed to convert rc_min bytes")?,
-    );
+    let public_input = VmPublicInput::from_bytes(public_input)?;
+    let (public_memory, memory_segments) = public_input.to_prover_parts();
 
-    let rc_max = u16::from_le_bytes(
-        public_input
-            .get(2..4)
-            .ok_or("Input must be at least 4 bytes long for rc_max")?
-            .try_into()
-            .map_err(|_| "Failed to convert rc_max bytes")?,
-    );
+    Ok((
+        public_input.rc_min,
+        public_input.rc_max,
+        public_memory,
+        memory_segments,
+    ))
+}
 
-    let mem_len = u64::from_le_bytes(
-        public_input
-            .get(4..12)
-            .ok_or("Input must be at least 12 bytes long for mem_len")?
-            .try_into()
-            .map_err(|_| "Failed to convert mem_len bytes")?,
-    ) as usize;
-
-    let mut public_memory: HashMap<Felt252, Felt252> = HashMap::new();
-    for i in 0..mem_len {
-        let start_index = 12 + i * 40;
-        let addr = Felt252::from(u64::from_le_bytes(
-            public_input
-                .get(start_index..start_index + 8)
-                .ok_or("Input too short for public memory address")?
-                .try_into()
-                .map_err(|_| "Failed to convert public memory address bytes")?,
-        ));
-        let value = Felt252::from_bytes_le(
-            public_input
-                .get(start_index + 8..start_index + 40)
-                .ok_or("Input too short for public memory value")?
-                .try_into()
-                .map_err(|_| "Failed to convert public memory value bytes")?,
-        )
-        .map_err(|_| "Failed to create Felt252 from bytes")?;
-        public_memory.insert(addr, value);
-    }
+// Convert public input bytes to JSON, either the VM public input taken by
+// cairo_prove or the bincode PublicInputs it returns.
+#[rustler::nif]
+fn cairo_public_input_to_json(
+    public_input: Binary,
+    format: PublicInputFormat,
+) -> NifResult<String> {
+    let json = match format {
+        PublicInputFormat::Vm => VmPublicInput::from_bytes(&public_input)
+            .map_err(PublicInputJsonError::PublicInputError)
+            .and_then(|public_input| to_json(&public_input)),
+        PublicInputFormat::Bincode => ProverPublicInput::from_bytes(&public_input)
+            .and_then(|public_input| to_json(&public_input)),
+    };
+    json.map_err(|e| Error::Term(Box::new(e)))
+}
 
-    let memory_segments_len = *public_input
-        .get(12 + 40 * mem_len)
-        .ok_or("Input too short for memory segments length")?
-        as usize;
-    let mut memory_segments = HashMap::new();
-    for i in 0..memory_segments_len {
-        let start_index = 12 + 40 * mem_len + 1 + i * 17;
-        let segment_type = match public_input
-            .get(start_index)
-            .ok_or("Input too short for segment type")?
-        {
-            0u8 => SegmentName::RangeCheck,
-            1u8 => SegmentName::Output,
-            2u8 => SegmentName::Program,
-            3u8 => SegmentName::Execution,
-            4u8 => SegmentName::Ecdsa,
-            5u8 => SegmentName::Pedersen,
-            _ => continue, // skip unknown type
-        };
-
-        let segment_begin = u64::from_le_bytes(
-            public_input
-                .get(start_index + 1..start_index + 9)
-                .ok_or("Input too short for segment begin")?
-                .try_into()
-                .map_err(|_| "Failed to convert segment begin bytes")?,
-        );
-        let segment_stop = u64::from_le_bytes(
-            public_input
-                .get(start_index + 9..start_index + 17)
-                .ok_or("Input too short for segment stop")?
-                .try_into()
-                .map_err(|_| "Failed to convert segment stop bytes")?,
-        );
-        memory_segments.insert(segment_type, Segment::new(segment_begin, segment_stop));
-    }
+#[rustler::nif]
+fn cairo_public_input_from_json(json: String, format: PublicInputFormat) -> NifResult<BinaryBytes> {
+    let bytes = match format {
+        PublicInputFormat::Vm => serde_json::from_str::<VmPublicInput>(&json)
+            .map_err(|e| PublicInputJsonError::InvalidJson(e.to_string()))
+            .and_then(|public_input| public_input.to_bytes()),
+        PublicInputFormat::Bincode => serde_json::from_str::<ProverPublicInput>(&json)
+            .map_err(|e| PublicInputJsonError::InvalidJson(e.to_string()))
+            .and_then(|public_input| public_input.to_bytes()),
+    };
+    bytes.map(BinaryBytes).map_err(|e| Error::Term(Box::new(e)))
+}
+
+#[derive(NifUnitEnum)]
+enum PublicInputFormat {
+    Vm,
+    Bincode,
+}
 
-    Ok((rc_min, rc_max, public_memory, memory_segments))
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, PublicInputJsonError> {
+    serde_json::to_string_pretty(value)
+        .map_err(|e| PublicInputJsonError::EncodingError(e.to_string()))
 }
 
 #[rustler::nif(schedule = "DirtyCpu")]
-fn cairo_verify(proof: Vec<u8>, public_input: Vec<u8>) -> NifResult<bool> {
-    let proof_options = ProofOptions::new_secure(SecurityLevel::Conjecturable100Bits, 3);
-
-    // Decode proof
-    let proof = bincode::serde::decode_from_slice(&proof, bincode::config::standard())
-        .map_err(|e| {
-            Error::Term(Box::new(CairoVerifyError::ProofDecodingError(
-                e.to_string(),
-            )))
-        })?
-        .0;
+fn cairo_verify<'a>(
+    proof: Binary<'a>,
+    public_input: Binary<'a>,
+    options: Option<CairoProofOptions>,
+) -> NifResult<bool> {
+    verify_proof(&proof, &public_input, options.as_ref()).map_err(|e| Error::Term(Box::new(e)))
+}
+
+// Verify the (proof, public_input) pairs in parallel. Each item gets its own
+// result, and the index of the first item that is not a valid proof is
+// returned along with them.
+#[rustler::nif(schedule = "DirtyCpu")]
+fn cairo_verify_batch<'a>(
+    items: Vec<(Binary<'a>, Binary<'a>)>,
+    options: Option<CairoProofOptions>,
+) -> (Vec<Result<bool, CairoVerifyError>>, Option<usize>) {
+    let items: Vec<(&[u8], &[u8])> = items
+        .iter()
+        .map(|(proof, public_input)| (proof.as_slice(), public_input.as_slice()))
+        .collect();
+
+    let results: Vec<Result<bool, CairoVerifyError>> = items
+        .par_iter()
+        .map(|(proof, public_input)| verify_proof(proof, public_input, options.as_ref()))
+        .collect();
+
+    let first_failure = results
+        .iter()
+        .position(|result| !matches!(result, Ok(true)));
+
+    (results, first_failure)
+}
+
+fn verify_proof(
+    proof: &[u8],
+    public_input: &[u8],
+    options: Option<&CairoProofOptions>,
+) -> Result<bool, CairoVerifyError> {
+    // Decode the proof envelope
+    let envelope = ProofEnvelope::from_bytes(proof)?;
+    let recorded_options = envelope
+        .options
+        .resolve()
+        .map_err(CairoVerifyError::ProofOptionsError)?;
+
+    // The proof must be at least as secure as the options given by the
+    // caller, the default ones if none, rather than the ones the prover chose
+    let expected_options = options
+        .cloned()
+        .unwrap_or_default()
+        .resolve()
+        .map_err(CairoVerifyError::ProofOptionsError)?;
+    if !recorded_options.is_at_least(&expected_options) {
+        return Err(CairoVerifyError::ProofOptionsMismatch);
+    }
+    let proof_options = recorded_options.to_proof_options();
 
     // Decode public inputs
-    let pub_inputs = bincode::serde::decode_from_slice(&public_input, bincode::config::standard())
-        .map_err(|e| {
-            Error::Term(Box::new(CairoVerifyError::PublicInputDecodingError(
-                e.to_string(),
-            )))
-        })?
+    let pub_inputs = bincode::serde::decode_from_slice(public_input, bincode::config::standard())
+        .map_err(|e| CairoVerifyError::PublicInputDecodingError(e.to_string()))?
         .0;
 
-    Ok(verify_cairo_proof(&proof, &pub_inputs, &proof_options))
+    Ok(verify_cairo_proof(
+        &envelope.proof,
+        &pub_inputs,
+        &proof_options,
+    ))
+}
+
+#[rustler::nif()]
+fn cairo_get_output(public_input: Binary) -> NifResult<Vec<BinaryBytes>> {
+    get_output(&public_input).map_err(|e| Error::Term(Box::new(e)))
 }
 
 #[rustler::nif()]
-fn cairo_get_output(public_input: Vec<u8>) -> NifResult<Vec<Vec<u8>>> {
+fn cairo_get_compliance_output(public_input: Binary) -> NifResult<ComplianceResult> {
+    get_output(&public_input)
+        .and_then(ComplianceResult::from_output)
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+#[rustler::nif()]
+fn cairo_get_logic_output(public_input: Binary) -> NifResult<LogicResult> {
+    get_output(&public_input)
+        .and_then(LogicResult::from_output)
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+fn get_output(public_input: &[u8]) -> Result<Vec<BinaryBytes>, CairoGetOutputError> {
     // Decode public inputs
     let (pub_inputs, _): (PublicInputs, usize) =
-        bincode::serde::decode_from_slice(&public_input, bincode::config::standard()).map_err(
-            |e| Error::Term(Box::new(CairoGetOutputError::DecodingError(e.to_string()))),
-        )?;
+        bincode::serde::decode_from_slice(public_input, bincode::config::standard())
+            .map_err(|e| CairoGetOutputError::DecodingError(e.to_string()))?;
 
     // Get output segments
     let output_segments = pub_inputs
         .memory_segments
         .get(&SegmentName::Output)
-        .ok_or_else(|| Error::Term(Box::new(CairoGetOutputError::SegmentNotFound)))?;
+        .ok_or(CairoGetOutputError::SegmentNotFound)?;
 
     let begin_addr: u64 = output_segments.begin_addr as u64;
     let stop_addr: u64 = output_segments.stop_ptr as u64;
@@ -239,11 +378,9 @@ fn cairo_get_output(public_input: Vec<u8>) -> NifResult<Vec<Vec<u8>>> {
         let addr_field_element = Felt252::from(addr);
 
         if let Some(value) = pub_inputs.public_memory.get(&addr_field_element) {
-            output_values.push(value.clone().to_bytes_be().to_vec());
+            output_values.push(value.clone().to_bytes_be().to_vec().into());
         } else {
-            return Err(Error::Term(Box::new(CairoGetOutputError::AddressNotFound(
-                addr,
-            ))));
+            return Err(CairoGetOutputError::AddressNotFound(addr));
         }
     }
 
@@ -253,110 +390,331 @@ fn cairo_get_output(public_input: Vec<u8>) -> NifResult<Vec<Vec<u8>>> {
 // The private_key_segments are random values used in delta commitments.
 // The messages are nullifiers and resource commitments in the transaction.
 #[rustler::nif]
-fn cairo_binding_sig_sign(
-    private_key_segments: Vec<u8>,
-    messages: Vec<Vec<u8>>,
-) -> NifResult<Vec<u8>> {
-    // Compute private key
-    let private_key = {
-        let result = private_key_segments
-            .chunks(32)
-            .fold(BigInt::zero(), |acc, key_segment| {
-                let key = BigInt::from_bytes_be(num_bigint::Sign::Plus, &key_segment);
-                acc.add(key)
-            })
-            .mod_floor(&EC_ORDER.to_bigint());
-
-        let (_, buffer) = result.to_bytes_be();
-        let mut result = [0u8; 32];
-        result[(32 - buffer.len())..].copy_from_slice(&buffer[..]);
+fn cairo_binding_sig_sign<'a>(
+    private_key_segments: Binary<'a>,
+    messages: Vec<Binary<'a>>,
+) -> NifResult<BinaryBytes> {
+    let private_key = binding_private_key(&private_key_segments);
+    let sig_hash = message_digest(&messages).map_err(|e| Error::Term(Box::new(e)))?;
+    binding_sig_sign(&private_key, &sig_hash)
+        .map(BinaryBytes)
+        .map_err(|e| Error::Term(Box::new(e)))
+}
 
-        Felt::from_bytes_be(&result)
-    };
+// The private key is the sum of the 32 bytes segments modulo the curve order
+fn binding_private_key(private_key_segments: &[u8]) -> Felt {
+    let result = private_key_segments
+        .chunks(32)
+        .fold(BigInt::zero(), |acc, key_segment| {
+            let key = BigInt::from_bytes_be(num_bigint::Sign::Plus, key_segment);
+            acc.add(key)
+        })
+        .mod_floor(&EC_ORDER.to_bigint());
 
-    // Message digest
-    let sig_hash = message_digest(messages)?;
+    let (_, buffer) = result.to_bytes_be();
+    let mut result = [0u8; 32];
+    result[(32 - buffer.len())..].copy_from_slice(&buffer[..]);
 
-    // ECDSA sign
-    let mut rng = thread_rng();
-    let k = {
-        let mut felt: [u8; 32] = Default::default();
-        rng.fill_bytes(&mut felt);
-        Felt::from_bytes_be(&felt)
-    };
-    let signature = sign(&private_key, &sig_hash, &k).map_err(|e| {
-        Error::Term(Box::new(CairoSignError::SignatureGenerationError(
-            e.to_string(),
-        )))
-    })?;
+    Felt::from_bytes_be(&result)
+}
 
-    // Serialize signature
+// The private_key_segments are summed as in cairo_binding_sig_sign. The
+// signature is r || s || v, v being the parity of the y coordinate of the
+// nonce point as a single byte, so that the public key can be recovered.
+#[rustler::nif]
+fn cairo_binding_sig_sign_recoverable<'a>(
+    private_key_segments: Binary<'a>,
+    messages: Vec<Binary<'a>>,
+) -> NifResult<BinaryBytes> {
+    let private_key = binding_private_key(&private_key_segments);
+    let sig_hash = message_digest(&messages).map_err(|e| Error::Term(Box::new(e)))?;
+    binding_sig_sign_recoverable(&private_key, &sig_hash)
+        .map(BinaryBytes)
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+fn binding_sig_sign(private_key: &Felt, sig_hash: &Felt) -> Result<Vec<u8>, CairoSignError> {
+    let signature = binding_sig_sign_extended(private_key, sig_hash)?;
+
+    // Serialize signature, v is only needed to recover the public key
     let mut ret = Vec::new();
     ret.extend(signature.r.to_bytes_be());
     ret.extend(signature.s.to_bytes_be());
-    // We don't need the v to recover pubkey
-    // ret.extend(signature.v.to_bytes_be());
     Ok(ret)
 }
 
+fn binding_sig_sign_recoverable(
+    private_key: &Felt,
+    sig_hash: &Felt,
+) -> Result<Vec<u8>, CairoSignError> {
+    let signature = binding_sig_sign_extended(private_key, sig_hash)?;
+
+    let mut ret = Vec::with_capacity(65);
+    ret.extend(signature.r.to_bytes_be());
+    ret.extend(signature.s.to_bytes_be());
+    ret.push(signature.v.to_bytes_be()[31]);
+    Ok(ret)
+}
+
+fn binding_sig_sign_extended(
+    private_key: &Felt,
+    sig_hash: &Felt,
+) -> Result<ExtendedSignature, CairoSignError> {
+    // ECDSA sign with the deterministic k of RFC 6979. The rare k that give
+    // an invalid signature are skipped by seeding the derivation.
+    let mut seed = None;
+    loop {
+        let k = rfc6979_generate_k(sig_hash, private_key, seed.as_ref());
+        match sign(private_key, sig_hash, &k) {
+            Ok(signature) => return Ok(signature),
+            Err(SignError::InvalidK) => seed = Some(seed.unwrap_or(Felt::ZERO) + Felt::ONE),
+            Err(e) => return Err(CairoSignError::SignatureGenerationError(e.to_string())),
+        }
+    }
+}
+
 // The pub_key_segments are delta commitments in compliance input inputs.
 #[rustler::nif]
-fn cairo_binding_sig_verify(
-    pub_key_segments: Vec<Vec<u8>>,
-    messages: Vec<Vec<u8>>,
-    signature: Vec<u8>,
+fn cairo_binding_sig_verify<'a>(
+    pub_key_segments: Vec<Binary<'a>>,
+    messages: Vec<Binary<'a>>,
+    signature: Binary<'a>,
 ) -> NifResult<bool> {
-    // Generate the public key
-    let pub_key = pub_key_segments
-        .into_iter()
+    let pub_key = binding_public_key(&pub_key_segments).map_err(|e| Error::Term(Box::new(e)))?;
+    let msg = message_digest(&messages).map_err(|e| Error::Term(Box::new(e)))?;
+    binding_sig_verify(&pub_key, &msg, &signature).map_err(|e| Error::Term(Box::new(e)))
+}
+
+// The public key is the x coordinate of the sum of the 64 bytes segments
+fn binding_public_key<B: Deref<Target = [u8]>>(
+    pub_key_segments: &[B],
+) -> Result<Felt, CairoBindingSigVerifyError> {
+    let pub_key = binding_public_point(pub_key_segments)?
+        .to_affine()
+        .map_err(|_| CairoBindingSigVerifyError::InputError)?;
+    Ok(pub_key.x())
+}
+
+// The sum of the 64 bytes segments
+fn binding_public_point<B: Deref<Target = [u8]>>(
+    pub_key_segments: &[B],
+) -> Result<ProjectivePoint, CairoBindingSigVerifyError> {
+    pub_key_segments
+        .iter()
         .try_fold(ProjectivePoint::identity(), |acc, bytes| {
-            let key_x = Felt::from_bytes_be(
-                &bytes[0..32]
-                    .try_into()
-                    .map_err(|_| CairoBindingSigVerifyError::InputError)?,
-            );
-            let key_y = Felt::from_bytes_be(
-                &bytes[32..64]
-                    .try_into()
-                    .map_err(|_| CairoBindingSigVerifyError::InputError)?,
-            );
-            let key_segment_affine = AffinePoint::new(key_x, key_y)
-                .map_err(|_| CairoBindingSigVerifyError::InputError)?;
-            Ok(acc.add(key_segment_affine))
+            Ok(acc.add(point_from_bytes(bytes)?))
         })
-        .map_err(|e: CairoBindingSigVerifyError| Error::Term(Box::new(e)))?
-        .to_affine()
-        .map_err(|_| Error::Term(Box::new(CairoBindingSigVerifyError::InputError)))?
-        .x();
+}
 
-    // Message digest
-    let msg = message_digest(messages)?;
+// A point as its x and y coordinates, 32 bytes each
+fn point_from_bytes(bytes: &[u8]) -> Result<AffinePoint, CairoBindingSigVerifyError> {
+    if bytes.len() != 64 {
+        return Err(CairoBindingSigVerifyError::InputError);
+    }
+    let x = felt_from_bytes(&bytes[0..32]).map_err(|_| CairoBindingSigVerifyError::InputError)?;
+    let y = felt_from_bytes(&bytes[32..64]).map_err(|_| CairoBindingSigVerifyError::InputError)?;
+    AffinePoint::new(x, y).map_err(|_| CairoBindingSigVerifyError::InputError)
+}
+
+// Recovers the x coordinate of the binding public key from a 65 bytes
+// signature of cairo_binding_sig_sign_recoverable.
+#[rustler::nif]
+fn cairo_binding_sig_recover<'a>(
+    messages: Vec<Binary<'a>>,
+    signature: Binary<'a>,
+) -> NifResult<BinaryBytes> {
+    let msg = message_digest(&messages).map_err(|e| Error::Term(Box::new(e)))?;
+    binding_sig_recover(&msg, &signature)
+        .map(|pub_key| pub_key.to_bytes_be().to_vec().into())
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+fn binding_sig_recover(msg: &Felt, signature: &[u8]) -> Result<Felt, CairoBindingSigVerifyError> {
+    if signature.len() != 65 || signature[64] > 1 {
+        return Err(CairoBindingSigVerifyError::InputError);
+    }
+    let r =
+        felt_from_bytes(&signature[0..32]).map_err(|_| CairoBindingSigVerifyError::InputError)?;
+    let s =
+        felt_from_bytes(&signature[32..64]).map_err(|_| CairoBindingSigVerifyError::InputError)?;
+    let v = Felt::from(signature[64]);
+
+    recover(msg, &r, &s, &v).map_err(|_| CairoBindingSigVerifyError::VerificationError)
+}
+
+// The signature is either r || s or the recoverable r || s || v, whose
+// recovered public key must be pub_key.
+fn binding_sig_verify(
+    pub_key: &Felt,
+    msg: &Felt,
+    signature: &[u8],
+) -> Result<bool, CairoBindingSigVerifyError> {
+    if signature.len() == 65 {
+        return Ok(binding_sig_recover(msg, signature)? == *pub_key);
+    }
 
     // Decode the signature
-    let r = Felt::from_bytes_be(
-        signature[0..32]
-            .try_into()
-            .map_err(|_| Error::Term(Box::new(CairoBindingSigVerifyError::InputError)))?,
-    );
-    let s = Felt::from_bytes_be(
-        signature[32..64]
-            .try_into()
-            .map_err(|_| Error::Term(Box::new(CairoBindingSigVerifyError::InputError)))?,
-    );
+    if signature.len() != 64 {
+        return Err(CairoBindingSigVerifyError::InputError);
+    }
+    let r =
+        felt_from_bytes(&signature[0..32]).map_err(|_| CairoBindingSigVerifyError::InputError)?;
+    let s =
+        felt_from_bytes(&signature[32..64]).map_err(|_| CairoBindingSigVerifyError::InputError)?;
 
     // Verify the signature
-    verify(&pub_key, &msg, &r, &s)
-        .map_err(|_| Error::Term(Box::new(CairoBindingSigVerifyError::VerificationError)))
+    verify(pub_key, msg, &r, &s).map_err(|_| CairoBindingSigVerifyError::VerificationError)
+}
+
+// Schnorr binding signature of a single signer, the private_key_segments
+// are summed as in cairo_binding_sig_sign
+#[rustler::nif]
+fn cairo_schnorr_sign<'a>(
+    private_key_segments: Binary<'a>,
+    messages: Vec<Binary<'a>>,
+) -> NifResult<BinaryBytes> {
+    let private_key = binding_private_key(&private_key_segments);
+    let msg = message_digest(&messages).map_err(|e| Error::Term(Box::new(e)))?;
+    schnorr::sign(&private_key, &msg)
+        .map(BinaryBytes)
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+#[rustler::nif]
+fn cairo_schnorr_verify<'a>(
+    pub_key_segments: Vec<Binary<'a>>,
+    messages: Vec<Binary<'a>>,
+    signature: Binary<'a>,
+) -> NifResult<bool> {
+    let pub_key = binding_public_point(&pub_key_segments).map_err(|e| Error::Term(Box::new(e)))?;
+    let msg = message_digest(&messages).map_err(|e| Error::Term(Box::new(e)))?;
+    schnorr::verify(&pub_key, &msg, &signature).map_err(|e| Error::Term(Box::new(e)))
+}
+
+// A random nonce pair and its commitments for a partial signature, the
+// nonces as 64 bytes and the commitments as 128 bytes
+#[rustler::nif]
+fn cairo_schnorr_nonce() -> NifResult<(BinaryBytes, BinaryBytes)> {
+    let (nonces, commitments) = schnorr::nonce();
+    let commitments = commitments
+        .iter()
+        .map(schnorr::point_to_bytes)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| Error::Term(Box::new(e)))?
+        .concat();
+    let nonces = [nonces[0].to_bytes_be(), nonces[1].to_bytes_be()].concat();
+    Ok((nonces.into(), commitments.into()))
+}
+
+// The partial signature of one signer. The nonce_commitments and the
+// pub_key_segments are those of all the signers, the pub_key_segments being
+// the deltas of compliance units, see schnorr.rs.
+#[rustler::nif]
+fn cairo_schnorr_partial_sign<'a>(
+    private_key_segments: Binary<'a>,
+    nonces: Binary<'a>,
+    nonce_commitments: Vec<Binary<'a>>,
+    pub_key_segments: Vec<Binary<'a>>,
+    messages: Vec<Binary<'a>>,
+) -> NifResult<BinaryBytes> {
+    let private_key = binding_private_key(&private_key_segments);
+    let invalid_nonce = || {
+        Error::Term(Box::new(CairoSignError::SignatureGenerationError(
+            "Invalid nonce".to_string(),
+        )))
+    };
+    if nonces.len() != 64 {
+        return Err(invalid_nonce());
+    }
+    let nonces = [
+        felt_from_bytes(&nonces[0..32]).map_err(|_| invalid_nonce())?,
+        felt_from_bytes(&nonces[32..64]).map_err(|_| invalid_nonce())?,
+    ];
+    let commitment =
+        schnorr::nonce_commitment(&nonce_commitments).map_err(|e| Error::Term(Box::new(e)))?;
+    let pub_key = binding_public_point(&pub_key_segments).map_err(|e| Error::Term(Box::new(e)))?;
+    let msg = message_digest(&messages).map_err(|e| Error::Term(Box::new(e)))?;
+    schnorr::partial_sign(&private_key, &nonces, &commitment, &pub_key, &msg)
+        .map(|s| s.to_bytes_be().to_vec().into())
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+// The signature over the combined key of the partial signatures, given the
+// same nonce commitments, public key segments and messages as them
+#[rustler::nif]
+fn cairo_schnorr_aggregate<'a>(
+    nonce_commitments: Vec<Binary<'a>>,
+    pub_key_segments: Vec<Binary<'a>>,
+    messages: Vec<Binary<'a>>,
+    partial_signatures: Vec<Binary<'a>>,
+) -> NifResult<BinaryBytes> {
+    let commitment =
+        schnorr::nonce_commitment(&nonce_commitments).map_err(|e| Error::Term(Box::new(e)))?;
+    let pub_key = binding_public_point(&pub_key_segments).map_err(|e| Error::Term(Box::new(e)))?;
+    let msg = message_digest(&messages).map_err(|e| Error::Term(Box::new(e)))?;
+    let partial_signatures =
+        felts_from_bytes(&partial_signatures).map_err(|e| Error::Term(Box::new(e)))?;
+    schnorr::aggregate(&commitment, &pub_key, &msg, &partial_signatures)
+        .map(BinaryBytes)
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+// Bundles the proofs of a transaction with its binding signature, signed
+// with the rcv values of its compliance units
+#[rustler::nif]
+fn cairo_transaction_create(
+    compliance_units: Vec<ProofRecord>,
+    logic_proofs: Vec<ProofRecord>,
+    rcvs: Vec<BinaryBytes>,
+) -> NifResult<Transaction> {
+    Transaction::new(compliance_units, logic_proofs, &rcvs).map_err(|e| Error::Term(Box::new(e)))
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn cairo_transaction_verify(
+    transaction: Transaction,
+    options: Option<CairoProofOptions>,
+) -> NifResult<Atom> {
+    transaction
+        .verify(options.as_ref())
+        .map(|_| rustler::types::atom::ok())
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+// Merges two partial transactions, the private_key_segments are the rcv
+// values of the compliance units of both
+#[rustler::nif]
+fn cairo_transaction_merge(
+    transaction: Transaction,
+    other: Transaction,
+    private_key_segments: Vec<BinaryBytes>,
+) -> NifResult<Transaction> {
+    transaction
+        .merge(other, &private_key_segments)
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+#[rustler::nif]
+fn cairo_transaction_to_bytes(transaction: Transaction) -> NifResult<BinaryBytes> {
+    transaction
+        .to_bytes()
+        .map(BinaryBytes)
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+#[rustler::nif]
+fn cairo_transaction_from_bytes(bytes: Binary) -> NifResult<Transaction> {
+    Transaction::from_bytes(&bytes).map_err(|e| Error::Term(Box::new(e)))
 }
 
 // random_felt can help create private key in signature
 #[rustler::nif]
-fn cairo_random_felt() -> NifResult<Vec<u8>> {
-    Ok(random_felt())
+fn cairo_random_felt() -> NifResult<BinaryBytes> {
+    Ok(random_felt().into())
 }
 
 #[rustler::nif]
-fn cairo_get_binding_sig_public_key(priv_key: Vec<u8>) -> NifResult<Vec<u8>> {
+fn cairo_get_binding_sig_public_key(priv_key: Binary) -> NifResult<BinaryBytes> {
     let priv_key_felt = Felt::from_bytes_be_slice(&priv_key);
 
     let generator = ProjectivePoint::from_affine(GENERATOR.x(), GENERATOR.y())
@@ -369,70 +727,308 @@ fn cairo_get_binding_sig_public_key(priv_key: Vec<u8>) -> NifResult<Vec<u8>> {
     let mut ret = pub_key.x().to_bytes_be().to_vec();
     let mut y = pub_key.y().to_bytes_be().to_vec();
     ret.append(&mut y);
-    Ok(ret)
+    Ok(ret.into())
 }
-fn message_digest(msg: Vec<Vec<u8>>) -> NifResult<Felt> {
-    let felt_msg_vec: Vec<Felt> = msg
-        .into_iter()
-        .map(|bytes| Felt::from_bytes_be(&bytes.try_into().expect("Slice with incorrect length")))
-        .collect();
-    Ok(poseidon_hash_many(&felt_msg_vec))
+fn message_digest(msg: &[Binary]) -> Result<Felt, FeltError> {
+    Ok(poseidon_hash_many(&felts_from_bytes(msg)?))
 }
 
 #[rustler::nif]
-fn poseidon_single(x: Vec<u8>) -> NifResult<Vec<u8>> {
-    let mut padded_x = x;
-    padded_x.resize(32, 0);
-    let x_bytes: [u8; 32] = padded_x
-        .as_slice()
-        .try_into()
-        .expect("Slice with incorrect length");
-    let x_field = Felt::from_bytes_be(&x_bytes);
-    Ok(poseidon_hash_single(x_field).to_bytes_be().to_vec())
-}
-
-#[rustler::nif]
-fn poseidon(x: Vec<u8>, y: Vec<u8>) -> NifResult<Vec<u8>> {
-    let x_bytes: [u8; 32] = x
-        .as_slice()
-        .try_into()
-        .expect("Slice with incorrect length");
-    let x_field = Felt::from_bytes_be(&x_bytes);
-    let y_bytes: [u8; 32] = y
-        .as_slice()
-        .try_into()
-        .expect("Slice with incorrect length");
-    let y_field = Felt::from_bytes_be(&y_bytes);
-    Ok(poseidon_hash(x_field, y_field).to_bytes_be().to_vec())
-}
-
-#[rustler::nif]
-fn poseidon_many(inputs: Vec<Vec<u8>>) -> NifResult<Vec<u8>> {
-    let mut vec_fe = Vec::new();
-    for i in inputs {
-        let i_bytes: [u8; 32] = i
-            .as_slice()
-            .try_into()
-            .expect("Slice with incorrect length");
-        vec_fe.push(Felt::from_bytes_be(&i_bytes))
+fn poseidon_single(x: Binary) -> NifResult<BinaryBytes> {
+    if x.len() > 32 {
+        return Err(Error::Term(Box::new(FeltError::InvalidLength(x.len()))));
     }
+    let mut padded_x = x.as_slice().to_vec();
+    padded_x.resize(32, 0);
+    let x_field = felt_from_bytes(&padded_x).map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(poseidon_hash_single(x_field).to_bytes_be().to_vec().into())
+}
+
+#[rustler::nif]
+fn poseidon<'a>(x: Binary<'a>, y: Binary<'a>) -> NifResult<BinaryBytes> {
+    let x_field = felt_from_bytes(&x).map_err(|e| Error::Term(Box::new(e)))?;
+    let y_field = felt_from_bytes(&y).map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(poseidon_hash(x_field, y_field)
+        .to_bytes_be()
+        .to_vec()
+        .into())
+}
+
+#[rustler::nif]
+fn poseidon_many(inputs: Vec<Binary>) -> NifResult<BinaryBytes> {
+    let vec_fe = felts_from_bytes(&inputs).map_err(|e| Error::Term(Box::new(e)))?;
     let result_fe = poseidon_hash_many(&vec_fe);
-    Ok(result_fe.to_bytes_be().to_vec())
+    Ok(result_fe.to_bytes_be().to_vec().into())
+}
+
+#[rustler::nif]
+fn cairo_resource_npk(nf_key: Binary) -> NifResult<BinaryBytes> {
+    let nf_key = felt_from_bytes(&nf_key).map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(resource::npk(&nf_key).to_bytes_be().to_vec().into())
+}
+
+#[rustler::nif]
+fn cairo_resource_psi(resource: Binary) -> NifResult<BinaryBytes> {
+    let resource = Resource::from_bytes(&resource).map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(resource.psi().to_bytes_be().to_vec().into())
+}
+
+#[rustler::nif]
+fn cairo_resource_rcm(resource: Binary) -> NifResult<BinaryBytes> {
+    let resource = Resource::from_bytes(&resource).map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(resource.rcm().to_bytes_be().to_vec().into())
+}
+
+#[rustler::nif]
+fn cairo_resource_commitment(resource: Binary) -> NifResult<BinaryBytes> {
+    let resource = Resource::from_bytes(&resource).map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(resource.commitment().to_bytes_be().to_vec().into())
+}
+
+#[rustler::nif]
+fn cairo_resource_commitment_as_output<'a>(
+    resource: Binary<'a>,
+    input_nullifier: Binary<'a>,
+) -> NifResult<BinaryBytes> {
+    let resource = Resource::from_bytes(&resource).map_err(|e| Error::Term(Box::new(e)))?;
+    let input_nullifier =
+        felt_from_bytes(&input_nullifier).map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(resource
+        .commitment_as_output(&input_nullifier)
+        .to_bytes_be()
+        .to_vec()
+        .into())
+}
+
+#[rustler::nif]
+fn cairo_resource_nullifier<'a>(
+    resource: Binary<'a>,
+    nf_key: Binary<'a>,
+) -> NifResult<BinaryBytes> {
+    let resource = Resource::from_bytes(&resource).map_err(|e| Error::Term(Box::new(e)))?;
+    let nf_key = felt_from_bytes(&nf_key).map_err(|e| Error::Term(Box::new(e)))?;
+    let nullifier = resource
+        .nullifier(&nf_key)
+        .map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(nullifier.to_bytes_be().to_vec().into())
+}
+
+#[rustler::nif]
+fn cairo_resource_kind(resource: Binary) -> NifResult<BinaryBytes> {
+    let kind = Resource::from_bytes(&resource)
+        .map_err(DeltaError::from)
+        .and_then(|resource| resource::point_to_bytes(&resource.kind()))
+        .map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(kind.into())
+}
+
+#[rustler::nif]
+fn cairo_resource_delta(resource: Binary) -> NifResult<BinaryBytes> {
+    let delta = Resource::from_bytes(&resource)
+        .map_err(DeltaError::from)
+        .and_then(|resource| resource::point_to_bytes(&resource.delta()))
+        .map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(delta.into())
+}
+
+#[rustler::nif]
+fn cairo_transaction_delta<'a>(
+    inputs: Vec<Binary<'a>>,
+    outputs: Vec<Binary<'a>>,
+    rcvs: Vec<Binary<'a>>,
+) -> NifResult<BinaryBytes> {
+    transaction_delta(&inputs, &outputs, &rcvs)
+        .map(BinaryBytes)
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+fn transaction_delta(
+    inputs: &[Binary],
+    outputs: &[Binary],
+    rcvs: &[Binary],
+) -> Result<Vec<u8>, DeltaError> {
+    let inputs = inputs
+        .iter()
+        .map(|bytes| Resource::from_bytes(bytes))
+        .collect::<Result<Vec<_>, _>>()?;
+    let outputs = outputs
+        .iter()
+        .map(|bytes| Resource::from_bytes(bytes))
+        .collect::<Result<Vec<_>, _>>()?;
+    let rcvs = felts_from_bytes(rcvs)?;
+    resource::point_to_bytes(&resource::transaction_delta(&inputs, &outputs, &rcvs))
+}
+
+// Returns a new ephemeral resource and its nf_key
+#[rustler::nif]
+fn cairo_ephemeral_resource<'a>(
+    logic: Binary<'a>,
+    label: Binary<'a>,
+    quantity: Binary<'a>,
+    data: Binary<'a>,
+) -> NifResult<(BinaryBytes, BinaryBytes)> {
+    let fields =
+        felts_from_bytes(&[logic, label, quantity, data]).map_err(|e| Error::Term(Box::new(e)))?;
+    let (resource, nf_key) = Resource::ephemeral(fields[0], fields[1], fields[2], fields[3]);
+    Ok((
+        resource.to_bytes().into(),
+        nf_key.to_bytes_be().to_vec().into(),
+    ))
+}
+
+#[rustler::nif]
+fn cairo_eph_root() -> BinaryBytes {
+    merkle::eph_root().to_bytes_be().to_vec().into()
+}
+
+#[rustler::nif]
+fn cairo_dummy_merkle_path() -> Vec<BinaryBytes> {
+    compliance_input::dummy_merkle_path()
+}
+
+#[rustler::nif]
+fn cairo_merkle_tree_new() -> ResourceArc<MerkleTreeResource> {
+    ResourceArc::new(MerkleTreeResource::new(MerkleTree::new()))
+}
+
+// Returns the position of the commitment in the tree
+#[rustler::nif]
+fn cairo_merkle_tree_append(
+    tree: ResourceArc<MerkleTreeResource>,
+    commitment: Binary,
+) -> NifResult<u64> {
+    let commitment = felt_from_bytes(&commitment)
+        .map_err(|e| Error::Term(Box::new(MerkleTreeError::from(e))))?;
+    tree.tree()
+        .append(commitment)
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+#[rustler::nif]
+fn cairo_merkle_tree_root(tree: ResourceArc<MerkleTreeResource>) -> BinaryBytes {
+    tree.tree().root().to_bytes_be().to_vec().into()
+}
+
+#[rustler::nif]
+fn cairo_merkle_tree_size(tree: ResourceArc<MerkleTreeResource>) -> u64 {
+    tree.tree().size()
+}
+
+// The siblings of the commitment at position, from the leaf up. Whether a
+// sibling is on the left is given by the bits of the position.
+#[rustler::nif]
+fn cairo_merkle_tree_path(
+    tree: ResourceArc<MerkleTreeResource>,
+    position: u64,
+) -> NifResult<Vec<BinaryBytes>> {
+    let path = tree
+        .tree()
+        .path(position)
+        .map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(path
+        .iter()
+        .map(|node| node.to_bytes_be().to_vec().into())
+        .collect())
+}
+
+// Whether root is one of the recent roots of the tree
+#[rustler::nif]
+fn cairo_merkle_tree_known_root(
+    tree: ResourceArc<MerkleTreeResource>,
+    root: Binary,
+) -> NifResult<bool> {
+    let root =
+        felt_from_bytes(&root).map_err(|e| Error::Term(Box::new(MerkleTreeError::from(e))))?;
+    Ok(tree.tree().is_known_root(&root))
+}
+
+#[rustler::nif]
+fn cairo_merkle_verify<'a>(
+    root: Binary<'a>,
+    commitment: Binary<'a>,
+    path: Vec<Binary<'a>>,
+    position: u64,
+) -> NifResult<bool> {
+    merkle_verify(&root, &commitment, &path, position).map_err(|e| Error::Term(Box::new(e)))
+}
+
+// Frontiers are passed serialized, so that they can be persisted as is
+#[rustler::nif]
+fn cairo_merkle_frontier_new() -> BinaryBytes {
+    MerkleFrontier::new().to_bytes().into()
+}
+
+// Returns the new frontier and the position of the commitment
+#[rustler::nif]
+fn cairo_merkle_frontier_append(
+    frontier: Binary,
+    commitment: Binary,
+) -> NifResult<(BinaryBytes, u64)> {
+    merkle_frontier_append(&frontier, &commitment).map_err(|e| Error::Term(Box::new(e)))
+}
+
+fn merkle_frontier_append(
+    frontier: &[u8],
+    commitment: &[u8],
+) -> Result<(BinaryBytes, u64), MerkleTreeError> {
+    let mut frontier = MerkleFrontier::from_bytes(frontier)?;
+    let position = frontier.append(felt_from_bytes(commitment)?)?;
+    Ok((frontier.to_bytes().into(), position))
+}
+
+#[rustler::nif]
+fn cairo_merkle_frontier_root(frontier: Binary) -> NifResult<BinaryBytes> {
+    let frontier = MerkleFrontier::from_bytes(&frontier).map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(frontier.root().to_bytes_be().to_vec().into())
+}
+
+#[rustler::nif]
+fn cairo_merkle_frontier_size(frontier: Binary) -> NifResult<u64> {
+    let frontier = MerkleFrontier::from_bytes(&frontier).map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(frontier.size())
+}
+
+// The path of the last commitment appended to the frontier
+#[rustler::nif]
+fn cairo_merkle_frontier_path(frontier: Binary) -> NifResult<Vec<BinaryBytes>> {
+    let path = MerkleFrontier::from_bytes(&frontier)
+        .and_then(|frontier| frontier.path())
+        .map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(path
+        .iter()
+        .map(|node| node.to_bytes_be().to_vec().into())
+        .collect())
+}
+
+fn merkle_verify(
+    root: &[u8],
+    commitment: &[u8],
+    path: &[Binary],
+    position: u64,
+) -> Result<bool, MerkleTreeError> {
+    let root = felt_from_bytes(root)?;
+    let commitment = felt_from_bytes(commitment)?;
+    let path = felts_from_bytes(path)?;
+    Ok(merkle::root_from_path(commitment, &path, position)? == root)
 }
 
 // Get the program from public inputs and return the program hash as the
 // resource label
 #[rustler::nif]
-fn program_hash(public_inputs: Vec<u8>) -> NifResult<Vec<u8>> {
+fn program_hash(public_inputs: Binary) -> NifResult<BinaryBytes> {
+    let program_hash =
+        program_hash_from_public_input(&public_inputs).map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(program_hash.to_bytes_be().to_vec().into())
+}
+
+fn program_hash_from_public_input(public_inputs: &[u8]) -> Result<Felt, CairoProgramHashError> {
     let (pub_inputs, _): (PublicInputs, usize) =
-        bincode::serde::decode_from_slice(&public_inputs, bincode::config::standard()).unwrap();
-    let program_segments = match pub_inputs.memory_segments.get(&SegmentName::Program) {
-        Some(segment) => segment,
-        None => {
-            eprintln!("Error: 'Program' segment not found in memory_segments");
-            return Ok(vec![]);
-        }
-    };
+        bincode::serde::decode_from_slice(public_inputs, bincode::config::standard())
+            .map_err(|e| CairoProgramHashError::DecodingError(e.to_string()))?;
+    let program_segments = pub_inputs
+        .memory_segments
+        .get(&SegmentName::Program)
+        .ok_or(CairoProgramHashError::SegmentNotFound)?;
 
     let begin_addr: u64 = program_segments.begin_addr as u64;
     let stop_addr: u64 = program_segments.stop_ptr as u64;
@@ -445,64 +1041,167 @@ fn program_hash(public_inputs: Vec<u8>) -> NifResult<Vec<u8>> {
         if let Some(value) = pub_inputs.public_memory.get(&addr_field_element) {
             program.push(Felt::from_raw(value.to_raw().limbs));
         } else {
-            eprintln!(
-                "Error: Address {:?} not found in public memory",
-                addr_field_element
-            );
-            return Ok(vec![]);
+            return Err(CairoProgramHashError::AddressNotFound(addr));
         }
     }
 
-    let program_hash = poseidon_hash_many(&program);
+    Ok(poseidon_hash_many(&program))
+}
+
+// The program segment of the public memory holds the data of the compiled
+// program, so hashing the data gives the same hash as program_hash does
+// for the proofs of the program.
+#[rustler::nif]
+fn program_hash_from_json(program_content: String) -> NifResult<BinaryBytes> {
+    let program_hash =
+        program_hash_from_program(&program_content).map_err(|e| Error::Term(Box::new(e)))?;
+    Ok(program_hash.to_bytes_be().to_vec().into())
+}
+
+fn program_hash_from_program(program_content: &str) -> Result<Felt, CairoProgramHashError> {
+    let program: CompiledProgram = serde_json::from_str(program_content)
+        .map_err(|_| CairoProgramHashError::InvalidProgramContent)?;
+
+    let data = program
+        .data
+        .iter()
+        .map(|value| {
+            Felt::from_hex(value)
+                .map_err(|_| CairoProgramHashError::InvalidProgramData(value.clone()))
+        })
+        .collect::<Result<Vec<Felt>, CairoProgramHashError>>()?;
 
-    Ok(program_hash.to_bytes_be().to_vec())
+    Ok(poseidon_hash_many(&data))
+}
+
+#[derive(serde::Deserialize)]
+struct CompiledProgram {
+    data: Vec<String>,
+}
+
+#[rustler::nif]
+fn cairo_felt_to_string(felt: Binary) -> NifResult<String> {
+    felt_to_string(&felt).map_err(|e| Error::Term(Box::new(e)))
+}
+
+// Validates the inputs of the compliance program and encodes them for the VM
+#[rustler::nif]
+fn cairo_generate_compliance_input_json(input: ComplianceInput) -> NifResult<String> {
+    ComplianceInputJson::new(&input)
+        .and_then(|json| json.to_json_string())
+        .map_err(|e| Error::Term(Box::new(e)))
+}
+
+// Pairs the inputs and the outputs of a transaction in compliance units, and
+// returns the JSON input, the rcv and the padding resources of each unit
+#[rustler::nif]
+fn cairo_generate_compliance_units_json(
+    inputs: Vec<InputResource>,
+    outputs: Vec<BinaryBytes>,
+) -> NifResult<Vec<(String, BinaryBytes, Padding)>> {
+    ComplianceInput::units(&inputs, &outputs)
+        .into_iter()
+        .map(|(unit, padding)| {
+            let json = ComplianceInputJson::new(&unit)?.to_json_string()?;
+            Ok((json, unit.rcv, padding))
+        })
+        .collect::<Result<_, ComplianceInputError>>()
+        .map_err(|e| Error::Term(Box::new(e)))
 }
 
 #[rustler::nif]
-fn cairo_felt_to_string(felt: Vec<u8>) -> String {
-    felt_to_string(&felt)
+fn cairo_parse_compliance_input_json(json: String) -> NifResult<ComplianceInputFields> {
+    let mut fields = ComplianceInputJson::from_json_str(&json)
+        .and_then(|json| json.to_fields())
+        .map_err(|e| Error::Term(Box::new(e)))?;
+    fields.source = Some(json);
+    Ok(fields)
 }
 
+// Encodes parsed compliance inputs back to JSON, in the formatting of the
+// JSON they were parsed from, without the checks of
+// cairo_generate_compliance_input_json
 #[rustler::nif]
-fn cairo_generate_compliance_input_json(
-    input_resource: Vec<u8>,
-    output_resource: Vec<u8>,
-    path: Vec<Vec<u8>>,
-    pos: u64,
-    input_nf_key: Vec<u8>,
-    eph_root: Vec<u8>,
-    rcv: Vec<u8>,
-) -> String {
-    ComplianceInputJson::to_json_string(
-        &input_resource,
-        &output_resource,
-        &path,
-        pos,
-        &input_nf_key,
-        &eph_root,
-        &rcv,
-    )
+fn cairo_encode_compliance_input_json(fields: ComplianceInputFields) -> NifResult<String> {
+    ComplianceInputJson::from_fields(&fields)
+        .and_then(|json| json.to_json_string_like(fields.source.as_deref()))
+        .map_err(|e| Error::Term(Box::new(e)))
 }
 
 rustler::init!(
     "Elixir.Cairo.CairoProver",
     [
         cairo_prove,
+        cairo_prove_async,
+        cairo_prove_cancel,
+        cairo_run_and_prove,
+        cairo_public_input_to_json,
+        cairo_public_input_from_json,
         cairo_verify,
+        cairo_verify_batch,
         cairo_get_output,
+        cairo_get_compliance_output,
+        cairo_get_logic_output,
         cairo_binding_sig_sign,
         cairo_binding_sig_verify,
+        cairo_binding_sig_sign_recoverable,
+        cairo_binding_sig_recover,
+        cairo_schnorr_sign,
+        cairo_schnorr_verify,
+        cairo_schnorr_nonce,
+        cairo_schnorr_partial_sign,
+        cairo_schnorr_aggregate,
+        cairo_transaction_create,
+        cairo_transaction_verify,
+        cairo_transaction_merge,
+        cairo_transaction_to_bytes,
+        cairo_transaction_from_bytes,
         cairo_random_felt,
         cairo_get_binding_sig_public_key,
         poseidon_single,
         poseidon,
         poseidon_many,
+        cairo_resource_npk,
+        cairo_resource_psi,
+        cairo_resource_rcm,
+        cairo_resource_commitment,
+        cairo_resource_commitment_as_output,
+        cairo_resource_nullifier,
+        cairo_resource_kind,
+        cairo_resource_delta,
+        cairo_transaction_delta,
+        cairo_ephemeral_resource,
+        cairo_eph_root,
+        cairo_dummy_merkle_path,
+        cairo_merkle_tree_new,
+        cairo_merkle_tree_append,
+        cairo_merkle_tree_root,
+        cairo_merkle_tree_size,
+        cairo_merkle_tree_path,
+        cairo_merkle_tree_known_root,
+        cairo_merkle_verify,
+        cairo_merkle_frontier_new,
+        cairo_merkle_frontier_append,
+        cairo_merkle_frontier_root,
+        cairo_merkle_frontier_size,
+        cairo_merkle_frontier_path,
         program_hash,
+        program_hash_from_json,
         cairo_felt_to_string,
         cairo_generate_compliance_input_json,
-    ]
+        cairo_generate_compliance_units_json,
+        cairo_parse_compliance_input_json,
+        cairo_encode_compliance_input_json,
+    ],
+    load = load
 );
 
+fn load(env: Env, _: Term) -> bool {
+    rustler::resource!(ProveJob, env);
+    rustler::resource!(MerkleTreeResource, env);
+    true
+}
+
 use lazy_static::lazy_static;
 lazy_static! {
     // Bytes: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 67, 97, 105, 114, 111, 95, 69, 120, 112, 97, 110, 100, 83, 101, 101, 100]
@@ -515,6 +1214,48 @@ lazy_static! {
 
         result.to_vec()
     };
+
+    // The hashes of the programs in cairo_vm: the compliance program, whose
+    // proofs are the compliance units of transactions, and the logic of the
+    // padding resources.
+    pub static ref COMPLIANCE_PROGRAM_HASH: Felt =
+        program_hash_from_program(include_str!("../../cairo_vm/compliance.json"))
+            .expect("The compliance program is valid");
+    pub static ref TRIVIAL_RESOURCE_LOGIC_HASH: Felt =
+        program_hash_from_program(include_str!("../../cairo_vm/trivial_resource_logic.json"))
+            .expect("The trivial resource logic is valid");
+}
+
+#[test]
+fn test_parse_public_input_segment_types() {
+    let public_input = |segment_type: u8| {
+        let mut bytes = vec![0, 0, 1, 0];
+        bytes.extend(0u64.to_le_bytes());
+        bytes.push(2);
+        for (segment_type, begin, stop) in [(1u8, 1u64, 3u64), (segment_type, 3, 5)] {
+            bytes.push(segment_type);
+            bytes.extend(begin.to_le_bytes());
+            bytes.extend(stop.to_le_bytes());
+        }
+        bytes
+    };
+
+    let (_, _, _, memory_segments) = parse_public_input(&public_input(0)).unwrap();
+    assert_eq!(memory_segments.len(), 2);
+
+    // The prover has no Poseidon segment, it is left out
+    let (_, _, _, memory_segments) = parse_public_input(&public_input(9)).unwrap();
+    assert_eq!(memory_segments.len(), 1);
+    assert!(memory_segments.contains_key(&SegmentName::Output));
+
+    let Err(error) = parse_public_input(&public_input(42)) else {
+        panic!("unknown segment type accepted");
+    };
+    assert_eq!(error.offset, 30);
+    assert!(matches!(
+        error.reason,
+        errors::PublicInputErrorReason::UnknownSegmentType
+    ));
 }
 
 #[test]
@@ -544,3 +1285,70 @@ fn generate_compliance_input_test_params() {
     println!("input_npk: {:?}", input_npk.to_bytes_be());
     println!("input_npk: {:?}", input_npk.to_hex_string());
 }
+
+#[test]
+fn test_binding_sig_sign_vectors() {
+    // (private key, message hash, r || s)
+    let vectors = [
+        (
+            "0x1",
+            "0x2",
+            "0543b191c671bc1f9b2f4e643a5711535cf34cb8330ab22e2416e8cdda8db054\
+             02f139920a75d2209e972b1bf82dc72e4c1edb8355fdbae7b4910ea7c32e70e2",
+        ),
+        (
+            "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+            "0x5b2a",
+            "03518754a7af45156a0ace2156437b3816e6f481e6471cb31cb68e0e6f3fb1a2\
+             063057449c4fd5f49def9efd15cde4ead126b35cabf090acf2d633ffd2a12a22",
+        ),
+        (
+            "0x139fe4d6f02e666e86a6f58e65060f115cd3c185bd9e98bd829636931458f79",
+            "0x6fea80189363a786037ed3e7ba546dad0ef7de49fccae0e31eb658b7dd4ea76",
+            "061ec782f76a66f6984efc3a1b6d152a124c701c00abdd2bf76641b4135c770f\
+             04e44e759cea02c23568bb4d8a09929bbca8768ab68270d50c18d214166ccd9a",
+        ),
+    ];
+    for (private_key, sig_hash, expected) in vectors {
+        let private_key = Felt::from_hex(private_key).unwrap();
+        let sig_hash = Felt::from_hex(sig_hash).unwrap();
+        let signature = binding_sig_sign(&private_key, &sig_hash).unwrap();
+        let hex: String = signature.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hex, expected);
+        assert_eq!(
+            binding_sig_sign(&private_key, &sig_hash).unwrap(),
+            signature
+        );
+    }
+}
+
+#[test]
+fn test_binding_sig_recover() {
+    let private_key =
+        Felt::from_hex("0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc")
+            .unwrap();
+    let pub_key =
+        Felt::from_hex("0x77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43")
+            .unwrap();
+    let sig_hash = Felt::from(0x5b2a_u64);
+
+    let signature = binding_sig_sign_recoverable(&private_key, &sig_hash).unwrap();
+    assert_eq!(signature.len(), 65);
+    assert_eq!(
+        signature[..64],
+        binding_sig_sign(&private_key, &sig_hash).unwrap()
+    );
+    assert_eq!(signature[64], 1);
+    assert_eq!(binding_sig_recover(&sig_hash, &signature).unwrap(), pub_key);
+    assert!(binding_sig_verify(&pub_key, &sig_hash, &signature).unwrap());
+    assert!(!binding_sig_verify(&pub_key, &Felt::ONE, &signature).unwrap());
+
+    let mut flipped = signature.clone();
+    flipped[64] = 0;
+    assert_ne!(binding_sig_recover(&sig_hash, &flipped).unwrap(), pub_key);
+    flipped[64] = 2;
+    assert!(matches!(
+        binding_sig_recover(&sig_hash, &flipped),
+        Err(CairoBindingSigVerifyError::InputError)
+    ));
+}
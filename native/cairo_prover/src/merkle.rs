@@ -0,0 +1,379 @@
+use crate::{errors::MerkleTreeError, utils::felt_from_bytes};
+use lazy_static::lazy_static;
+use starknet_crypto::poseidon_hash;
+use starknet_types_core::felt::Felt;
+use std::{
+    collections::VecDeque,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+// The depth of the commitment tree, the length of the Merkle paths taken by
+// the compliance program.
+pub const TREE_DEPTH: usize = 32;
+// The number of recent roots a tree remembers.
+pub const ROOT_HISTORY_SIZE: usize = 100;
+// The size of a serialized frontier: its size, its last leaf and its nodes.
+pub const FRONTIER_BYTES: usize = 8 + 32 * (TREE_DEPTH + 1);
+
+lazy_static! {
+    // The roots of the empty subtrees of each height, empty leaves are zero.
+    pub static ref EMPTY_NODES: Vec<Felt> = {
+        let mut nodes = vec![Felt::ZERO];
+        for i in 0..TREE_DEPTH {
+            nodes.push(poseidon_hash(nodes[i], nodes[i]));
+        }
+        nodes
+    };
+}
+
+// The root output by the compliance program for ephemeral inputs, the root
+// of the empty tree. It is a known root of every tree.
+pub fn eph_root() -> Felt {
+    EMPTY_NODES[TREE_DEPTH]
+}
+
+// The path of ephemeral inputs, ignored by the compliance program. It is the
+// path of the first leaf of the empty tree, which leads to eph_root.
+pub fn dummy_path() -> Vec<Felt> {
+    EMPTY_NODES[..TREE_DEPTH].to_vec()
+}
+
+// Hash a node with its sibling the way check_merkle in compliance.juvix
+// does: the position bit of the node tells whether the sibling is on its
+// left.
+pub fn hash_with_sibling(node: Felt, sibling: Felt, sibling_is_left: bool) -> Felt {
+    if sibling_is_left {
+        poseidon_hash(sibling, node)
+    } else {
+        poseidon_hash(node, sibling)
+    }
+}
+
+// The root given by the path of the leaf at position.
+pub fn root_from_path(leaf: Felt, path: &[Felt], position: u64) -> Result<Felt, MerkleTreeError> {
+    if path.len() < 64 && position >> path.len() != 0 {
+        return Err(MerkleTreeError::InvalidPosition(position));
+    }
+    Ok(path.iter().enumerate().fold(leaf, |node, (i, sibling)| {
+        hash_with_sibling(node, *sibling, (position >> i) & 1 == 1)
+    }))
+}
+
+// An append only Poseidon Merkle tree of the resource commitments.
+pub struct MerkleTree {
+    // The non empty nodes of each level, from the leaves to the root.
+    levels: Vec<Vec<Felt>>,
+    roots: VecDeque<Felt>,
+}
+
+impl Default for MerkleTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MerkleTree {
+    pub fn new() -> Self {
+        Self {
+            levels: vec![Vec::new(); TREE_DEPTH + 1],
+            roots: VecDeque::from([EMPTY_NODES[TREE_DEPTH]]),
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.levels[0].len() as u64
+    }
+
+    // Returns the position of the leaf.
+    pub fn append(&mut self, leaf: Felt) -> Result<u64, MerkleTreeError> {
+        let position = self.size();
+        if position >> TREE_DEPTH != 0 {
+            return Err(MerkleTreeError::TreeFull);
+        }
+
+        self.levels[0].push(leaf);
+        let mut index = position as usize;
+        for height in 0..TREE_DEPTH {
+            let level = &self.levels[height];
+            let left = level[index & !1];
+            let right = level.get(index | 1).copied().unwrap_or(EMPTY_NODES[height]);
+            let parent = poseidon_hash(left, right);
+
+            index >>= 1;
+            let parents = &mut self.levels[height + 1];
+            if index < parents.len() {
+                parents[index] = parent;
+            } else {
+                parents.push(parent);
+            }
+        }
+
+        if self.roots.len() == ROOT_HISTORY_SIZE {
+            self.roots.pop_front();
+        }
+        self.roots.push_back(self.root());
+        Ok(position)
+    }
+
+    pub fn root(&self) -> Felt {
+        self.levels[TREE_DEPTH]
+            .first()
+            .copied()
+            .unwrap_or(EMPTY_NODES[TREE_DEPTH])
+    }
+
+    // The siblings of the leaf at position, from the leaf up.
+    pub fn path(&self, position: u64) -> Result<Vec<Felt>, MerkleTreeError> {
+        if position >= self.size() {
+            return Err(MerkleTreeError::InvalidPosition(position));
+        }
+        Ok((0..TREE_DEPTH)
+            .map(|height| {
+                let index = (position >> height) as usize ^ 1;
+                self.levels[height]
+                    .get(index)
+                    .copied()
+                    .unwrap_or(EMPTY_NODES[height])
+            })
+            .collect())
+    }
+
+    // One of the last ROOT_HISTORY_SIZE roots, or eph_root which is never
+    // evicted.
+    pub fn is_known_root(&self, root: &Felt) -> bool {
+        *root == eph_root() || self.roots.contains(root)
+    }
+}
+
+// The frontier of a tree: its last leaf and the left nodes on its path, with
+// its size. It is enough to append leaves, get the path of the last one and
+// the root, without storing the other leaves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleFrontier {
+    size: u64,
+    leaf: Felt,
+    // Computed from the leaf and the nodes, never read from bytes.
+    root: Felt,
+    // The node of each height that is the left child of its parent on the
+    // path of the last leaf, the path of the next leaf reuses them.
+    nodes: Vec<Felt>,
+}
+
+impl Default for MerkleFrontier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MerkleFrontier {
+    pub fn new() -> Self {
+        Self {
+            size: 0,
+            leaf: Felt::ZERO,
+            root: EMPTY_NODES[TREE_DEPTH],
+            nodes: vec![Felt::ZERO; TREE_DEPTH],
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn root(&self) -> Felt {
+        self.root
+    }
+
+    // Returns the position of the leaf.
+    pub fn append(&mut self, leaf: Felt) -> Result<u64, MerkleTreeError> {
+        let position = self.size;
+        if position >> TREE_DEPTH != 0 {
+            return Err(MerkleTreeError::TreeFull);
+        }
+
+        self.set_last_leaf(leaf, position);
+        self.size += 1;
+        Ok(position)
+    }
+
+    // Hash the leaf at position up to the root, keeping the nodes on its
+    // path that are left children. Their right siblings are still empty.
+    fn set_last_leaf(&mut self, leaf: Felt, position: u64) {
+        let mut node = leaf;
+        for height in 0..TREE_DEPTH {
+            let is_right = (position >> height) & 1 == 1;
+            if !is_right {
+                self.nodes[height] = node;
+            }
+            let sibling = if is_right {
+                self.nodes[height]
+            } else {
+                EMPTY_NODES[height]
+            };
+            node = hash_with_sibling(node, sibling, is_right);
+        }
+
+        self.leaf = leaf;
+        self.root = node;
+    }
+
+    // The siblings of the last leaf, from the leaf up. The subtrees on its
+    // right are empty.
+    pub fn path(&self) -> Result<Vec<Felt>, MerkleTreeError> {
+        let position = self
+            .size
+            .checked_sub(1)
+            .ok_or(MerkleTreeError::InvalidPosition(0))?;
+        Ok((0..TREE_DEPTH)
+            .map(|height| {
+                if (position >> height) & 1 == 1 {
+                    self.nodes[height]
+                } else {
+                    EMPTY_NODES[height]
+                }
+            })
+            .collect())
+    }
+
+    // The size as 8 bytes big-endian, then the last leaf and the nodes as
+    // 32 bytes big-endian felts.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(FRONTIER_BYTES);
+        bytes.extend(self.size.to_be_bytes());
+        bytes.extend(self.leaf.to_bytes_be());
+        for node in &self.nodes {
+            bytes.extend(node.to_bytes_be());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MerkleTreeError> {
+        if bytes.len() != FRONTIER_BYTES {
+            return Err(MerkleTreeError::InvalidFrontierLength(bytes.len()));
+        }
+        let mut size = [0u8; 8];
+        size.copy_from_slice(&bytes[0..8]);
+        let size = u64::from_be_bytes(size);
+        if size > 1 << TREE_DEPTH {
+            return Err(MerkleTreeError::InvalidPosition(size));
+        }
+
+        let mut felts = bytes[8..]
+            .chunks(32)
+            .map(felt_from_bytes)
+            .collect::<Result<Vec<_>, _>>()?;
+        let nodes = felts.split_off(1);
+        let mut frontier = Self {
+            nodes,
+            ..Self::new()
+        };
+
+        // The root and the nodes on the path of the last leaf are recomputed
+        // rather than trusted.
+        if let Some(position) = size.checked_sub(1) {
+            frontier.set_last_leaf(felts[0], position);
+            frontier.size = size;
+        }
+        Ok(frontier)
+    }
+}
+
+// A tree shared with Elixir.
+pub struct MerkleTreeResource(Mutex<MerkleTree>);
+
+impl MerkleTreeResource {
+    pub fn new(tree: MerkleTree) -> Self {
+        Self(Mutex::new(tree))
+    }
+
+    // The tree is left consistent by every operation, even one that panics.
+    pub fn tree(&self) -> MutexGuard<MerkleTree> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[test]
+fn test_merkle_tree_paths() {
+    let mut tree = MerkleTree::new();
+    assert_eq!(tree.root(), EMPTY_NODES[TREE_DEPTH]);
+
+    let leaves: Vec<Felt> = (1..=5u64).map(Felt::from).collect();
+    for (i, leaf) in leaves.iter().enumerate() {
+        assert_eq!(tree.append(*leaf).unwrap(), i as u64);
+    }
+
+    let root = tree.root();
+    for (i, leaf) in leaves.iter().enumerate() {
+        let path = tree.path(i as u64).unwrap();
+        assert_eq!(path.len(), TREE_DEPTH);
+        assert_eq!(root_from_path(*leaf, &path, i as u64).unwrap(), root);
+    }
+
+    // The first leaves are hashed in order
+    let path = tree.path(0).unwrap();
+    assert_eq!(path[0], leaves[1]);
+    assert_eq!(path[1], poseidon_hash(leaves[2], leaves[3]));
+
+    assert!(tree.is_known_root(&root));
+    assert!(tree.is_known_root(&eph_root()));
+    assert_eq!(
+        root_from_path(Felt::ZERO, &dummy_path(), 0).unwrap(),
+        eph_root()
+    );
+    assert!(matches!(
+        tree.path(5),
+        Err(MerkleTreeError::InvalidPosition(5))
+    ));
+}
+
+#[test]
+fn test_merkle_tree_root_history() {
+    let mut tree = MerkleTree::new();
+    tree.append(Felt::ONE).unwrap();
+    let first_root = tree.root();
+
+    for i in 0..ROOT_HISTORY_SIZE as u64 {
+        tree.append(Felt::from(i + 2)).unwrap();
+    }
+    assert!(!tree.is_known_root(&first_root));
+    assert!(tree.is_known_root(&tree.root()));
+    assert!(tree.is_known_root(&eph_root()));
+}
+
+#[test]
+fn test_merkle_frontier_matches_tree() {
+    let mut tree = MerkleTree::new();
+    let mut frontier = MerkleFrontier::new();
+    assert_eq!(frontier.root(), tree.root());
+    assert!(frontier.path().is_err());
+
+    for i in 1..=9u64 {
+        let leaf = Felt::from(i);
+        assert_eq!(frontier.append(leaf).unwrap(), tree.append(leaf).unwrap());
+        assert_eq!(frontier.root(), tree.root());
+        assert_eq!(frontier.path().unwrap(), tree.path(i - 1).unwrap());
+
+        frontier = MerkleFrontier::from_bytes(&frontier.to_bytes()).unwrap();
+        assert_eq!(frontier.size(), tree.size());
+        assert_eq!(frontier.root(), tree.root());
+    }
+
+    // Nodes on the path of the last leaf don't change the decoded root, and
+    // the leaf does
+    let mut bytes = frontier.to_bytes();
+    bytes[FRONTIER_BYTES - 1] ^= 1;
+    assert_eq!(
+        MerkleFrontier::from_bytes(&bytes).unwrap().root(),
+        tree.root()
+    );
+    let mut bytes = frontier.to_bytes();
+    bytes[8 + 31] ^= 1;
+    assert_ne!(
+        MerkleFrontier::from_bytes(&bytes).unwrap().root(),
+        tree.root()
+    );
+
+    assert!(matches!(
+        MerkleFrontier::from_bytes(&[0; 8]),
+        Err(MerkleTreeError::InvalidFrontierLength(8))
+    ));
+}
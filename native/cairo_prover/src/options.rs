@@ -0,0 +1,299 @@
+use rustler::{Atom, Decoder, Encoder, Env, NifResult, NifStruct, Term};
+use serde::{Deserialize, Serialize};
+use stark_platinum_prover::proof::options::{ProofOptions, SecurityLevel};
+use starknet_types_core::felt::Felt;
+
+rustler::atoms! {
+    conjecturable_80_bits,
+    conjecturable_100_bits,
+    conjecturable_128_bits,
+    provable_80_bits,
+    provable_100_bits,
+    provable_128_bits,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CairoSecurityLevel {
+    Conjecturable80Bits,
+    Conjecturable100Bits,
+    Conjecturable128Bits,
+    Provable80Bits,
+    Provable100Bits,
+    Provable128Bits,
+}
+
+impl CairoSecurityLevel {
+    fn bits(&self) -> usize {
+        match self {
+            CairoSecurityLevel::Conjecturable80Bits | CairoSecurityLevel::Provable80Bits => 80,
+            CairoSecurityLevel::Conjecturable100Bits | CairoSecurityLevel::Provable100Bits => 100,
+            CairoSecurityLevel::Conjecturable128Bits | CairoSecurityLevel::Provable128Bits => 128,
+        }
+    }
+
+    fn is_provable(&self) -> bool {
+        matches!(
+            self,
+            CairoSecurityLevel::Provable80Bits
+                | CairoSecurityLevel::Provable100Bits
+                | CairoSecurityLevel::Provable128Bits
+        )
+    }
+
+    fn to_security_level(self) -> SecurityLevel {
+        match self {
+            CairoSecurityLevel::Conjecturable80Bits => SecurityLevel::Conjecturable80Bits,
+            CairoSecurityLevel::Conjecturable100Bits => SecurityLevel::Conjecturable100Bits,
+            CairoSecurityLevel::Conjecturable128Bits => SecurityLevel::Conjecturable128Bits,
+            CairoSecurityLevel::Provable80Bits => SecurityLevel::Provable80Bits,
+            CairoSecurityLevel::Provable100Bits => SecurityLevel::Provable100Bits,
+            CairoSecurityLevel::Provable128Bits => SecurityLevel::Provable128Bits,
+        }
+    }
+}
+
+impl Encoder for CairoSecurityLevel {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        match self {
+            CairoSecurityLevel::Conjecturable80Bits => conjecturable_80_bits(),
+            CairoSecurityLevel::Conjecturable100Bits => conjecturable_100_bits(),
+            CairoSecurityLevel::Conjecturable128Bits => conjecturable_128_bits(),
+            CairoSecurityLevel::Provable80Bits => provable_80_bits(),
+            CairoSecurityLevel::Provable100Bits => provable_100_bits(),
+            CairoSecurityLevel::Provable128Bits => provable_128_bits(),
+        }
+        .encode(env)
+    }
+}
+
+impl<'a> Decoder<'a> for CairoSecurityLevel {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        let atom: Atom = term.decode()?;
+        if atom == conjecturable_80_bits() {
+            Ok(CairoSecurityLevel::Conjecturable80Bits)
+        } else if atom == conjecturable_100_bits() {
+            Ok(CairoSecurityLevel::Conjecturable100Bits)
+        } else if atom == conjecturable_128_bits() {
+            Ok(CairoSecurityLevel::Conjecturable128Bits)
+        } else if atom == provable_80_bits() {
+            Ok(CairoSecurityLevel::Provable80Bits)
+        } else if atom == provable_100_bits() {
+            Ok(CairoSecurityLevel::Provable100Bits)
+        } else if atom == provable_128_bits() {
+            Ok(CairoSecurityLevel::Provable128Bits)
+        } else {
+            Err(rustler::Error::BadArg)
+        }
+    }
+}
+
+// The options mirror `Cairo.ProofOptions`. Unset (nil) parameters are taken
+// from the lambdaworks defaults of the chosen security level.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, NifStruct)]
+#[module = "Cairo.ProofOptions"]
+pub struct CairoProofOptions {
+    pub security_level: CairoSecurityLevel,
+    pub blowup_factor: Option<u8>,
+    pub fri_number_of_queries: Option<usize>,
+    pub grinding_factor: Option<u8>,
+    pub coset_offset: u64,
+}
+
+impl Default for CairoProofOptions {
+    fn default() -> Self {
+        Self {
+            security_level: CairoSecurityLevel::Conjecturable100Bits,
+            blowup_factor: None,
+            fri_number_of_queries: None,
+            grinding_factor: None,
+            coset_offset: 3,
+        }
+    }
+}
+
+impl CairoProofOptions {
+    // Fill in the unset parameters and check that custom parameters still
+    // reach the conjectured security of the chosen level. That is not the
+    // provable security, so custom parameters are rejected with provable
+    // levels. The resolved options are the ones recorded in the proof.
+    pub fn resolve(&self) -> Result<Self, String> {
+        self.check_coset_offset()?;
+
+        let proof_options = self.to_proof_options();
+        let resolved = Self {
+            security_level: self.security_level,
+            blowup_factor: Some(proof_options.blowup_factor),
+            fri_number_of_queries: Some(proof_options.fri_number_of_queries),
+            grinding_factor: Some(proof_options.grinding_factor),
+            coset_offset: self.coset_offset,
+        };
+
+        let secure = self.secure_options();
+        if proof_options.blowup_factor != secure.blowup_factor
+            || proof_options.fri_number_of_queries != secure.fri_number_of_queries
+            || proof_options.grinding_factor != secure.grinding_factor
+        {
+            if self.security_level.is_provable() {
+                return Err(
+                    "custom parameters are only supported with conjecturable security levels"
+                        .to_string(),
+                );
+            }
+            resolved.check_security()?;
+        }
+
+        Ok(resolved)
+    }
+
+    fn secure_options(&self) -> ProofOptions {
+        ProofOptions::new_secure(self.security_level.to_security_level(), self.coset_offset)
+    }
+
+    // The LDE is evaluated on the coset of a subgroup of the 2^192 roots of
+    // unity of the Stark field, so the offset must be nonzero and not one of
+    // them, or the coset overlaps the trace domain.
+    fn check_coset_offset(&self) -> Result<(), String> {
+        let offset = Felt::from(self.coset_offset);
+        let power = (0..192).fold(offset, |power, _| power * power);
+        if offset == Felt::ZERO || power == Felt::ONE {
+            return Err(format!(
+                "coset offset {} is zero or a root of unity",
+                self.coset_offset
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_security(&self) -> Result<(), String> {
+        let blowup_factor = self.blowup_factor.unwrap_or_default();
+        if blowup_factor < 2 || !blowup_factor.is_power_of_two() {
+            return Err(format!(
+                "blowup factor {} is not a power of two greater than 1",
+                blowup_factor
+            ));
+        }
+
+        let fri_number_of_queries = self.fri_number_of_queries.unwrap_or_default();
+        if fri_number_of_queries == 0 {
+            return Err("the number of FRI queries must be positive".to_string());
+        }
+
+        let security_bits = self.security_bits();
+        if security_bits < self.security_level.bits() {
+            return Err(format!(
+                "options give {} bits of conjectured security, {} required",
+                security_bits,
+                self.security_level.bits()
+            ));
+        }
+
+        Ok(())
+    }
+
+    // The conjectured security of resolved options
+    fn security_bits(&self) -> usize {
+        self.grinding_factor.unwrap_or_default() as usize
+            + self.blowup_factor.unwrap_or_default().trailing_zeros() as usize
+                * self.fri_number_of_queries.unwrap_or_default()
+    }
+
+    // Whether resolved options are at least as secure as the expected ones:
+    // the same or a higher security level, provable if the expected level
+    // is, and as many bits of conjectured security.
+    pub fn is_at_least(&self, expected: &Self) -> bool {
+        self.security_level.bits() >= expected.security_level.bits()
+            && (self.security_level.is_provable() || !expected.security_level.is_provable())
+            && self.security_bits() >= expected.security_bits()
+    }
+
+    pub fn to_proof_options(&self) -> ProofOptions {
+        let secure = self.secure_options();
+        ProofOptions {
+            blowup_factor: self.blowup_factor.unwrap_or(secure.blowup_factor),
+            fri_number_of_queries: self
+                .fri_number_of_queries
+                .unwrap_or(secure.fri_number_of_queries),
+            coset_offset: self.coset_offset,
+            grinding_factor: self.grinding_factor.unwrap_or(secure.grinding_factor),
+        }
+    }
+}
+
+#[test]
+fn test_default_options_match_previous_settings() {
+    let expected = ProofOptions::new_secure(SecurityLevel::Conjecturable100Bits, 3);
+    let options = CairoProofOptions::default().to_proof_options();
+
+    assert_eq!(options.blowup_factor, expected.blowup_factor);
+    assert_eq!(
+        options.fri_number_of_queries,
+        expected.fri_number_of_queries
+    );
+    assert_eq!(options.coset_offset, expected.coset_offset);
+    assert_eq!(options.grinding_factor, expected.grinding_factor);
+}
+
+#[test]
+fn test_insecure_custom_options_are_rejected() {
+    let options = CairoProofOptions {
+        blowup_factor: Some(2),
+        fri_number_of_queries: Some(10),
+        ..Default::default()
+    };
+    assert!(options.resolve().is_err());
+
+    let options = CairoProofOptions {
+        blowup_factor: Some(3),
+        ..Default::default()
+    };
+    assert!(options.resolve().is_err());
+
+    // Enough conjectured security, but provable levels take no custom
+    // parameters
+    let options = CairoProofOptions {
+        security_level: CairoSecurityLevel::Provable80Bits,
+        blowup_factor: Some(16),
+        fri_number_of_queries: Some(30),
+        ..Default::default()
+    };
+    assert!(options.resolve().is_err());
+    let options = CairoProofOptions {
+        security_level: CairoSecurityLevel::Provable80Bits,
+        ..Default::default()
+    };
+    assert!(options.resolve().is_ok());
+}
+
+#[test]
+fn test_degenerate_coset_offsets_are_rejected() {
+    for coset_offset in [0, 1] {
+        let options = CairoProofOptions {
+            coset_offset,
+            ..Default::default()
+        };
+        assert!(options.resolve().is_err());
+    }
+    let options = CairoProofOptions {
+        coset_offset: 7,
+        ..Default::default()
+    };
+    assert!(options.resolve().is_ok());
+}
+
+#[test]
+fn test_weaker_options_are_not_at_least_expected() {
+    let resolve = |security_level| {
+        CairoProofOptions {
+            security_level,
+            ..Default::default()
+        }
+        .resolve()
+        .unwrap()
+    };
+    let expected = CairoProofOptions::default().resolve().unwrap();
+
+    assert!(expected.is_at_least(&expected));
+    assert!(resolve(CairoSecurityLevel::Conjecturable128Bits).is_at_least(&expected));
+    assert!(!resolve(CairoSecurityLevel::Conjecturable80Bits).is_at_least(&expected));
+    assert!(!expected.is_at_least(&resolve(CairoSecurityLevel::Provable100Bits)));
+}
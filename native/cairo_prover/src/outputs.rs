@@ -0,0 +1,65 @@
+use crate::{
+    errors::{CairoGetOutputError, OutputLength},
+    utils::BinaryBytes,
+};
+use rustler::NifStruct;
+
+// The result of the compliance program in compliance.juvix, in the order
+// it writes the fields to the output segment.
+#[derive(NifStruct)]
+#[module = "Cairo.ComplianceResult"]
+pub struct ComplianceResult {
+    pub input_nullifier: BinaryBytes,
+    pub output_cm: BinaryBytes,
+    pub root: BinaryBytes,
+    pub delta_x: BinaryBytes,
+    pub delta_y: BinaryBytes,
+    pub input_logic: BinaryBytes,
+    pub output_logic: BinaryBytes,
+}
+
+impl ComplianceResult {
+    pub fn from_output(output: Vec<BinaryBytes>) -> Result<Self, CairoGetOutputError> {
+        let [input_nullifier, output_cm, root, delta_x, delta_y, input_logic, output_logic] =
+            fixed_output(output)?;
+        Ok(Self {
+            input_nullifier,
+            output_cm,
+            root,
+            delta_x,
+            delta_y,
+            input_logic,
+            output_logic,
+        })
+    }
+}
+
+// The result of resource logic programs, e.g. trivial_resource_logic.juvix.
+#[derive(NifStruct)]
+#[module = "Cairo.LogicResult"]
+pub struct LogicResult {
+    pub self_resource_id: BinaryBytes,
+    pub root: BinaryBytes,
+}
+
+impl LogicResult {
+    pub fn from_output(output: Vec<BinaryBytes>) -> Result<Self, CairoGetOutputError> {
+        let [self_resource_id, root] = fixed_output(output)?;
+        Ok(Self {
+            self_resource_id,
+            root,
+        })
+    }
+}
+
+fn fixed_output<const N: usize>(
+    output: Vec<BinaryBytes>,
+) -> Result<[BinaryBytes; N], CairoGetOutputError> {
+    let actual = output.len();
+    output.try_into().map_err(|_| {
+        CairoGetOutputError::InvalidOutputLength(OutputLength {
+            expected: N,
+            actual,
+        })
+    })
+}
@@ -0,0 +1,435 @@
+use crate::errors::{PublicInputError, PublicInputErrorReason, PublicInputJsonError};
+use cairo_platinum_prover::{
+    air::{PublicInputs, Segment, SegmentName},
+    Felt252,
+};
+use hashbrown::HashMap;
+use lambdaworks_math::traits::ByteConversion;
+use serde::{Deserialize, Serialize};
+use starknet_types_core::felt::Felt;
+
+// The public input bytes written by the Cairo VM runner, all integers are
+// little-endian:
+//
+//   offset 0   rc_min                u16
+//   offset 2   rc_max                u16
+//   offset 4   mem_len               u64
+//   offset 12  mem_len memory cells  40 bytes each
+//                address             u64
+//                value               32 bytes felt
+//   then       segments_len          u8
+//              segments_len segments 17 bytes each
+//                type                u8, see SegmentType
+//                begin_addr          u64
+//                stop_ptr            u64
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VmPublicInput {
+    pub rc_min: u16,
+    pub rc_max: u16,
+    pub public_memory: Vec<MemoryCell>,
+    pub memory_segments: Vec<MemorySegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryCell {
+    pub address: u64,
+    #[serde(with = "felt_hex")]
+    pub value: Felt252,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemorySegment {
+    #[serde(rename = "type")]
+    pub segment_type: SegmentType,
+    pub begin_addr: u64,
+    pub stop_ptr: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentType {
+    RangeCheck = 0,
+    Output = 1,
+    Program = 2,
+    Execution = 3,
+    Ecdsa = 4,
+    Pedersen = 5,
+    Bitwise = 6,
+    EcOp = 7,
+    Keccak = 8,
+    Poseidon = 9,
+}
+
+impl SegmentType {
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(SegmentType::RangeCheck),
+            1 => Some(SegmentType::Output),
+            2 => Some(SegmentType::Program),
+            3 => Some(SegmentType::Execution),
+            4 => Some(SegmentType::Ecdsa),
+            5 => Some(SegmentType::Pedersen),
+            6 => Some(SegmentType::Bitwise),
+            7 => Some(SegmentType::EcOp),
+            8 => Some(SegmentType::Keccak),
+            9 => Some(SegmentType::Poseidon),
+            _ => None,
+        }
+    }
+
+    // The lambdaworks prover has no segment names for the Bitwise, EcOp,
+    // Keccak and Poseidon builtins and its AIR doesn't constrain them.
+    pub fn segment_name(self) -> Option<SegmentName> {
+        match self {
+            SegmentType::RangeCheck => Some(SegmentName::RangeCheck),
+            SegmentType::Output => Some(SegmentName::Output),
+            SegmentType::Program => Some(SegmentName::Program),
+            SegmentType::Execution => Some(SegmentName::Execution),
+            SegmentType::Ecdsa => Some(SegmentName::Ecdsa),
+            SegmentType::Pedersen => Some(SegmentName::Pedersen),
+            _ => None,
+        }
+    }
+
+    pub fn from_segment_name(name: &SegmentName) -> Self {
+        match name {
+            SegmentName::RangeCheck => SegmentType::RangeCheck,
+            SegmentName::Output => SegmentType::Output,
+            SegmentName::Program => SegmentType::Program,
+            SegmentName::Execution => SegmentType::Execution,
+            SegmentName::Ecdsa => SegmentType::Ecdsa,
+            SegmentName::Pedersen => SegmentType::Pedersen,
+        }
+    }
+}
+
+impl std::fmt::Display for SegmentType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            SegmentType::RangeCheck => "range_check",
+            SegmentType::Output => "output",
+            SegmentType::Program => "program",
+            SegmentType::Execution => "execution",
+            SegmentType::Ecdsa => "ecdsa",
+            SegmentType::Pedersen => "pedersen",
+            SegmentType::Bitwise => "bitwise",
+            SegmentType::EcOp => "ec_op",
+            SegmentType::Keccak => "keccak",
+            SegmentType::Poseidon => "poseidon",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl VmPublicInput {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PublicInputError> {
+        let rc_min = u16::from_le_bytes(read_bytes(
+            bytes,
+            0,
+            PublicInputErrorReason::TruncatedRcMin,
+        )?);
+        let rc_max = u16::from_le_bytes(read_bytes(
+            bytes,
+            2,
+            PublicInputErrorReason::TruncatedRcMax,
+        )?);
+        let mem_len = u64::from_le_bytes(read_bytes(
+            bytes,
+            4,
+            PublicInputErrorReason::TruncatedMemoryLength,
+        )?) as usize;
+
+        let mut public_memory = Vec::new();
+        for i in 0..mem_len {
+            let start_index = 12 + i * 40;
+            let address = u64::from_le_bytes(read_bytes(
+                bytes,
+                start_index,
+                PublicInputErrorReason::TruncatedMemoryAddress,
+            )?);
+            let value = Felt252::from_bytes_le(&read_bytes::<32>(
+                bytes,
+                start_index + 8,
+                PublicInputErrorReason::TruncatedMemoryValue,
+            )?)
+            .map_err(|_| PublicInputError {
+                offset: start_index + 8,
+                reason: PublicInputErrorReason::InvalidMemoryValue,
+            })?;
+            public_memory.push(MemoryCell { address, value });
+        }
+
+        let [memory_segments_len] = read_bytes(
+            bytes,
+            12 + 40 * mem_len,
+            PublicInputErrorReason::TruncatedSegmentsLength,
+        )?;
+        let mut memory_segments = Vec::new();
+        for i in 0..memory_segments_len as usize {
+            let start_index = segment_offset(mem_len, i);
+            let [segment_type] = read_bytes(
+                bytes,
+                start_index,
+                PublicInputErrorReason::TruncatedSegmentType,
+            )?;
+            let begin_addr = u64::from_le_bytes(read_bytes(
+                bytes,
+                start_index + 1,
+                PublicInputErrorReason::TruncatedSegmentBegin,
+            )?);
+            let stop_ptr = u64::from_le_bytes(read_bytes(
+                bytes,
+                start_index + 9,
+                PublicInputErrorReason::TruncatedSegmentStop,
+            )?);
+            let segment_type = SegmentType::from_byte(segment_type).ok_or(PublicInputError {
+                offset: start_index,
+                reason: PublicInputErrorReason::UnknownSegmentType,
+            })?;
+            memory_segments.push(MemorySegment {
+                segment_type,
+                begin_addr,
+                stop_ptr,
+            });
+        }
+
+        Ok(Self {
+            rc_min,
+            rc_max,
+            public_memory,
+            memory_segments,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, PublicInputJsonError> {
+        let memory_segments_len = u8::try_from(self.memory_segments.len()).map_err(|_| {
+            PublicInputJsonError::EncodingError(format!(
+                "{} memory segments, at most 255 fit in the public input",
+                self.memory_segments.len()
+            ))
+        })?;
+
+        let mut bytes = Vec::with_capacity(
+            13 + 40 * self.public_memory.len() + 17 * self.memory_segments.len(),
+        );
+        bytes.extend(self.rc_min.to_le_bytes());
+        bytes.extend(self.rc_max.to_le_bytes());
+        bytes.extend((self.public_memory.len() as u64).to_le_bytes());
+        for cell in &self.public_memory {
+            bytes.extend(cell.address.to_le_bytes());
+            bytes.extend(cell.value.to_bytes_le());
+        }
+        bytes.push(memory_segments_len);
+        for segment in &self.memory_segments {
+            bytes.push(segment.segment_type as u8);
+            bytes.extend(segment.begin_addr.to_le_bytes());
+            bytes.extend(segment.stop_ptr.to_le_bytes());
+        }
+        Ok(bytes)
+    }
+
+    // The public memory and the segments in the form the prover takes them.
+    // The PublicInputs of the prover have no place for the segments of the
+    // Bitwise, EcOp, Keccak and Poseidon builtins, which its AIR doesn't
+    // constrain, so they are left out: the proof doesn't cover the outputs
+    // of those builtins.
+    pub fn to_prover_parts(&self) -> (HashMap<Felt252, Felt252>, HashMap<SegmentName, Segment>) {
+        let public_memory = self
+            .public_memory
+            .iter()
+            .map(|cell| (Felt252::from(cell.address), cell.value.clone()))
+            .collect();
+        let memory_segments = self
+            .memory_segments
+            .iter()
+            .filter_map(|segment| {
+                let name = segment.segment_type.segment_name()?;
+                Some((name, Segment::new(segment.begin_addr, segment.stop_ptr)))
+            })
+            .collect();
+        (public_memory, memory_segments)
+    }
+}
+
+// The bincode encoded PublicInputs returned by the prover, with the public
+// memory sorted by address and the segments by type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProverPublicInput {
+    #[serde(with = "felt_hex")]
+    pub pc_init: Felt252,
+    #[serde(with = "felt_hex")]
+    pub ap_init: Felt252,
+    #[serde(with = "felt_hex")]
+    pub fp_init: Felt252,
+    #[serde(with = "felt_hex")]
+    pub pc_final: Felt252,
+    #[serde(with = "felt_hex")]
+    pub ap_final: Felt252,
+    pub range_check_min: Option<u16>,
+    pub range_check_max: Option<u16>,
+    pub memory_segments: Vec<MemorySegment>,
+    pub public_memory: Vec<MemoryCell>,
+    pub num_steps: usize,
+}
+
+impl ProverPublicInput {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PublicInputJsonError> {
+        let (pub_inputs, _): (PublicInputs, usize) =
+            bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+                .map_err(|e| PublicInputJsonError::DecodingError(e.to_string()))?;
+
+        let mut public_memory = pub_inputs
+            .public_memory
+            .iter()
+            .map(|(address, value)| {
+                Ok(MemoryCell {
+                    address: felt_to_address(address)?,
+                    value: value.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, PublicInputJsonError>>()?;
+        public_memory.sort_by_key(|cell| cell.address);
+
+        let mut memory_segments: Vec<MemorySegment> = pub_inputs
+            .memory_segments
+            .iter()
+            .map(|(name, segment)| MemorySegment {
+                segment_type: SegmentType::from_segment_name(name),
+                begin_addr: segment.begin_addr as u64,
+                stop_ptr: segment.stop_ptr as u64,
+            })
+            .collect();
+        memory_segments.sort_by_key(|segment| segment.segment_type);
+
+        Ok(Self {
+            pc_init: pub_inputs.pc_init,
+            ap_init: pub_inputs.ap_init,
+            fp_init: pub_inputs.fp_init,
+            pc_final: pub_inputs.pc_final,
+            ap_final: pub_inputs.ap_final,
+            range_check_min: pub_inputs.range_check_min,
+            range_check_max: pub_inputs.range_check_max,
+            memory_segments,
+            public_memory,
+            num_steps: pub_inputs.num_steps,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, PublicInputJsonError> {
+        let memory_segments = self
+            .memory_segments
+            .iter()
+            .map(|segment| {
+                let name = segment.segment_type.segment_name().ok_or(
+                    PublicInputJsonError::UnsupportedSegmentType(segment.segment_type),
+                )?;
+                Ok((name, Segment::new(segment.begin_addr, segment.stop_ptr)))
+            })
+            .collect::<Result<_, PublicInputJsonError>>()?;
+        let public_memory = self
+            .public_memory
+            .iter()
+            .map(|cell| (Felt252::from(cell.address), cell.value.clone()))
+            .collect();
+
+        let pub_inputs = PublicInputs {
+            pc_init: self.pc_init.clone(),
+            ap_init: self.ap_init.clone(),
+            fp_init: self.fp_init.clone(),
+            pc_final: self.pc_final.clone(),
+            ap_final: self.ap_final.clone(),
+            range_check_min: self.range_check_min,
+            range_check_max: self.range_check_max,
+            memory_segments,
+            public_memory,
+            num_steps: self.num_steps,
+        };
+        bincode::serde::encode_to_vec(&pub_inputs, bincode::config::standard())
+            .map_err(|e| PublicInputJsonError::EncodingError(e.to_string()))
+    }
+}
+
+// The offset of the i-th segment, after mem_len memory cells
+fn segment_offset(mem_len: usize, i: usize) -> usize {
+    12 + 40 * mem_len + 1 + i * 17
+}
+
+// Read N bytes of the public input at offset, or fail with reason.
+fn read_bytes<const N: usize>(
+    bytes: &[u8],
+    offset: usize,
+    reason: PublicInputErrorReason,
+) -> Result<[u8; N], PublicInputError> {
+    bytes
+        .get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(PublicInputError { offset, reason })
+}
+
+fn felt_to_address(felt: &Felt252) -> Result<u64, PublicInputJsonError> {
+    let felt = Felt::from_bytes_be_slice(&felt.to_bytes_be());
+    match felt.to_le_digits() {
+        [address, 0, 0, 0] => Ok(address),
+        _ => Err(PublicInputJsonError::InvalidAddress(felt.to_hex_string())),
+    }
+}
+
+// Felts are written to JSON as hex strings.
+mod felt_hex {
+    use cairo_platinum_prover::Felt252;
+    use lambdaworks_math::traits::ByteConversion;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use starknet_types_core::felt::Felt;
+
+    pub fn serialize<S: Serializer>(value: &Felt252, serializer: S) -> Result<S::Ok, S::Error> {
+        let felt = Felt::from_bytes_be_slice(&value.to_bytes_be());
+        serializer.serialize_str(&felt.to_hex_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Felt252, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        let felt =
+            Felt::from_hex(&hex).map_err(|_| D::Error::custom(format!("invalid felt {}", hex)))?;
+        Felt252::from_bytes_be(&felt.to_bytes_be())
+            .map_err(|_| D::Error::custom(format!("invalid felt {}", hex)))
+    }
+}
+
+#[test]
+fn test_vm_public_input_round_trip() {
+    let public_input = VmPublicInput {
+        rc_min: 1,
+        rc_max: 300,
+        public_memory: vec![
+            MemoryCell {
+                address: 1,
+                value: Felt252::from(17u64),
+            },
+            MemoryCell {
+                address: 2,
+                value: -Felt252::from(1u64),
+            },
+        ],
+        memory_segments: vec![
+            MemorySegment {
+                segment_type: SegmentType::Output,
+                begin_addr: 10,
+                stop_ptr: 11,
+            },
+            MemorySegment {
+                segment_type: SegmentType::Poseidon,
+                begin_addr: 11,
+                stop_ptr: 14,
+            },
+        ],
+    };
+
+    let bytes = public_input.to_bytes().unwrap();
+    assert_eq!(bytes.len(), 12 + 2 * 40 + 1 + 2 * 17);
+    assert_eq!(VmPublicInput::from_bytes(&bytes).unwrap(), public_input);
+
+    let json = serde_json::to_string(&public_input).unwrap();
+    assert!(json.contains(r#""type":"poseidon""#));
+    let decoded: VmPublicInput = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.to_bytes().unwrap(), bytes);
+}
@@ -0,0 +1,311 @@
+use crate::{
+    errors::{DeltaError, ResourceError},
+    utils::{felt_from_bytes, random_felt},
+    PRF_EXPAND_PERSONALIZATION_FELT, TRIVIAL_RESOURCE_LOGIC_HASH,
+};
+use starknet_crypto::{poseidon_hash, poseidon_hash_many};
+use starknet_curve::curve_params::{
+    GENERATOR, PEDERSEN_P0, PEDERSEN_P1, PEDERSEN_P2, PEDERSEN_P3, SHIFT_POINT,
+};
+use starknet_types_core::{
+    curve::{AffinePoint, ProjectivePoint},
+    felt::Felt,
+};
+
+pub const RESOURCE_BYTES: usize = 225;
+
+// A resource as laid out in the 225 bytes passed to the NIFs: logic, label,
+// quantity, data, nonce, npk and rseed as 32 bytes big-endian felts, then
+// the eph byte. The computations follow compliance.juvix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resource {
+    pub logic: Felt,
+    pub label: Felt,
+    pub quantity: Felt,
+    pub data: Felt,
+    pub eph: bool,
+    pub nonce: Felt,
+    pub npk: Felt,
+    pub rseed: Felt,
+}
+
+impl Resource {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ResourceError> {
+        if bytes.len() != RESOURCE_BYTES {
+            return Err(ResourceError::InvalidLength(bytes.len()));
+        }
+        Ok(Self {
+            logic: felt_from_bytes(&bytes[0..32])?,
+            label: felt_from_bytes(&bytes[32..64])?,
+            quantity: felt_from_bytes(&bytes[64..96])?,
+            data: felt_from_bytes(&bytes[96..128])?,
+            nonce: felt_from_bytes(&bytes[128..160])?,
+            npk: felt_from_bytes(&bytes[160..192])?,
+            rseed: felt_from_bytes(&bytes[192..224])?,
+            eph: bytes[224] != 0,
+        })
+    }
+
+    // A new ephemeral resource with a fresh nonce, rseed and nf_key, returned
+    // with the resource.
+    pub fn ephemeral(logic: Felt, label: Felt, quantity: Felt, data: Felt) -> (Self, Felt) {
+        let nf_key = Felt::from_bytes_be_slice(&random_felt());
+        let resource = Self {
+            logic,
+            label,
+            quantity,
+            data,
+            eph: true,
+            nonce: Felt::from_bytes_be_slice(&random_felt()),
+            npk: npk(&nf_key),
+            rseed: Felt::from_bytes_be_slice(&random_felt()),
+        };
+        (resource, nf_key)
+    }
+
+    // An ephemeral resource of quantity zero. It pads the compliance units of
+    // a transaction without changing its delta. Its logic is the trivial
+    // resource logic, which it needs a proof of like any other resource.
+    pub fn padding() -> (Self, Felt) {
+        Self::ephemeral(
+            *TRIVIAL_RESOURCE_LOGIC_HASH,
+            Felt::ZERO,
+            Felt::ZERO,
+            Felt::ZERO,
+        )
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(RESOURCE_BYTES);
+        for felt in [
+            self.logic,
+            self.label,
+            self.quantity,
+            self.data,
+            self.nonce,
+            self.npk,
+            self.rseed,
+        ] {
+            bytes.extend(felt.to_bytes_be());
+        }
+        bytes.push(self.eph as u8);
+        bytes
+    }
+
+    pub fn psi(&self) -> Felt {
+        prf_expand(Felt::ZERO, &self.rseed, &self.nonce)
+    }
+
+    pub fn rcm(&self) -> Felt {
+        prf_expand(Felt::ONE, &self.rseed, &self.nonce)
+    }
+
+    pub fn commitment(&self) -> Felt {
+        poseidon_hash_many(&[
+            self.logic,
+            self.label,
+            self.data,
+            self.npk,
+            self.nonce,
+            self.psi(),
+            self.quantity,
+            Felt::from(self.eph as u8),
+            self.rcm(),
+        ])
+    }
+
+    // The commitment the compliance program computes for the resource as the
+    // output of a unit, with the input nullifier of the unit as its nonce.
+    pub fn commitment_as_output(&self, input_nullifier: &Felt) -> Felt {
+        Self {
+            nonce: *input_nullifier,
+            ..self.clone()
+        }
+        .commitment()
+    }
+
+    // The nf_key must be the one the npk of the resource is derived from.
+    pub fn nullifier(&self, nf_key: &Felt) -> Result<Felt, ResourceError> {
+        if npk(nf_key) != self.npk {
+            return Err(ResourceError::InvalidNullifierKey);
+        }
+        Ok(poseidon_hash_many(&[
+            self.npk,
+            self.nonce,
+            self.psi(),
+            self.commitment(),
+        ]))
+    }
+
+    // The kind of the resource, pedersenHashToCurve of its logic and label.
+    pub fn kind(&self) -> ProjectivePoint {
+        kind(&self.logic, &self.label)
+    }
+
+    pub fn delta(&self) -> ProjectivePoint {
+        &self.kind() * self.quantity
+    }
+}
+
+pub fn npk(nf_key: &Felt) -> Felt {
+    poseidon_hash(*nf_key, Felt::ZERO)
+}
+
+// The point whose x coordinate is the Pedersen hash of x and y, as
+// pedersenHashToCurve computes it in the Juvix Cairo stdlib.
+pub fn kind(x: &Felt, y: &Felt) -> ProjectivePoint {
+    let (x_low, x_high) = split_felt(x);
+    let (y_low, y_high) = split_felt(y);
+
+    curve_point(&SHIFT_POINT)
+        + &curve_point(&PEDERSEN_P0) * x_low
+        + &curve_point(&PEDERSEN_P1) * x_high
+        + &curve_point(&PEDERSEN_P2) * y_low
+        + &curve_point(&PEDERSEN_P3) * y_high
+}
+
+// The delta of a set of compliance units: the kinds of the inputs times
+// their quantities, minus the ones of the outputs, blinded by the sum of
+// the rcv values.
+pub fn transaction_delta(
+    inputs: &[Resource],
+    outputs: &[Resource],
+    rcvs: &[Felt],
+) -> ProjectivePoint {
+    let generator = curve_point(&GENERATOR);
+    let mut delta = ProjectivePoint::identity();
+    for input in inputs {
+        delta += input.delta();
+    }
+    for output in outputs {
+        delta -= output.delta();
+    }
+    for rcv in rcvs {
+        delta += &generator * *rcv;
+    }
+    delta
+}
+
+// Points are passed as the 64 bytes of their x and y coordinates.
+pub fn point_to_bytes(point: &ProjectivePoint) -> Result<Vec<u8>, DeltaError> {
+    let point = point.to_affine().map_err(|_| DeltaError::PointAtInfinity)?;
+    let mut bytes = point.x().to_bytes_be().to_vec();
+    bytes.extend(point.y().to_bytes_be());
+    Ok(bytes)
+}
+
+// The curve parameters are points of the curve.
+fn curve_point(point: &AffinePoint) -> ProjectivePoint {
+    ProjectivePoint::from_affine_unchecked(point.x(), point.y())
+}
+
+// Split a felt in its low 248 bits and its high 4 bits.
+fn split_felt(felt: &Felt) -> (Felt, Felt) {
+    let mut low = felt.to_bytes_be();
+    let high = Felt::from(low[0]);
+    low[0] = 0;
+    (Felt::from_bytes_be(&low), high)
+}
+
+fn prf_expand(tag: Felt, rseed: &Felt, nonce: &Felt) -> Felt {
+    poseidon_hash_many(&[
+        Felt::from_bytes_be_slice(&PRF_EXPAND_PERSONALIZATION_FELT),
+        tag,
+        *rseed,
+        *nonce,
+    ])
+}
+
+#[test]
+fn test_resource_from_bytes() {
+    let mut bytes = Vec::new();
+    for _ in 0..7 {
+        bytes.extend(random_felt());
+    }
+    bytes.push(1);
+
+    let resource = Resource::from_bytes(&bytes).unwrap();
+    assert!(resource.eph);
+    assert_eq!(resource.to_bytes(), bytes);
+    assert_eq!(resource.rseed, Felt::from_bytes_be_slice(&bytes[192..224]));
+    assert!(matches!(
+        Resource::from_bytes(&bytes[1..]),
+        Err(ResourceError::InvalidLength(224))
+    ));
+}
+
+#[test]
+fn test_resource_nullifier_checks_nf_key() {
+    let nf_key = Felt::ONE;
+    let resource = Resource {
+        logic: Felt::from(1u8),
+        label: Felt::from(2u8),
+        quantity: Felt::from(3u8),
+        data: Felt::from(4u8),
+        eph: false,
+        nonce: Felt::from(5u8),
+        npk: npk(&nf_key),
+        rseed: Felt::from(6u8),
+    };
+
+    assert!(resource.nullifier(&nf_key).is_ok());
+
+    let nullifier = resource.nullifier(&nf_key).unwrap();
+    let output = Resource {
+        nonce: nullifier,
+        ..resource.clone()
+    };
+    assert_eq!(
+        resource.commitment_as_output(&nullifier),
+        output.commitment()
+    );
+    assert_ne!(
+        resource.commitment_as_output(&nullifier),
+        resource.commitment()
+    );
+
+    let (ephemeral, nf_key) = Resource::ephemeral(Felt::ONE, Felt::TWO, Felt::ONE, Felt::ZERO);
+    assert!(ephemeral.eph);
+    assert!(ephemeral.nullifier(&nf_key).is_ok());
+    assert_ne!(ephemeral.nonce, Resource::padding().0.nonce);
+    assert!(matches!(
+        resource.nullifier(&Felt::TWO),
+        Err(ResourceError::InvalidNullifierKey)
+    ));
+}
+
+#[test]
+fn test_kind_is_the_pedersen_hash_point() {
+    use starknet_crypto::pedersen_hash;
+
+    let logic = Felt::from_bytes_be_slice(&random_felt());
+    let label = Felt::from_bytes_be_slice(&random_felt());
+    let kind = kind(&logic, &label).to_affine().unwrap();
+    assert_eq!(kind.x(), pedersen_hash(&logic, &label));
+}
+
+#[test]
+fn test_balanced_transaction_delta() {
+    let resource = Resource {
+        logic: Felt::from(1u8),
+        label: Felt::from(2u8),
+        quantity: Felt::from(3u8),
+        data: Felt::ZERO,
+        eph: false,
+        nonce: Felt::ZERO,
+        npk: Felt::ZERO,
+        rseed: Felt::ZERO,
+    };
+    let rcv = Felt::from(7u8);
+
+    let delta = transaction_delta(&[resource.clone()], &[resource], &[rcv]);
+    let blinding = &curve_point(&GENERATOR) * rcv;
+    assert_eq!(
+        point_to_bytes(&delta).unwrap(),
+        point_to_bytes(&blinding).unwrap()
+    );
+    assert!(matches!(
+        point_to_bytes(&ProjectivePoint::identity()),
+        Err(DeltaError::PointAtInfinity)
+    ));
+}
@@ -0,0 +1,303 @@
+use crate::{
+    errors::{CairoBindingSigVerifyError, CairoSignError},
+    point_from_bytes,
+    utils::{felt_from_bytes, random_felt},
+};
+use num_bigint::BigInt;
+use num_integer::Integer;
+use starknet_crypto::{poseidon_hash_many, rfc6979_generate_k};
+use starknet_curve::curve_params::{EC_ORDER, GENERATOR};
+use starknet_types_core::{
+    curve::{AffinePoint, ProjectivePoint},
+    felt::Felt,
+};
+use std::ops::Deref;
+
+// A Schnorr binding signature on the Stark curve: the nonce commitment R as
+// x || y, then s, with s * G == R + e * P for the public key P and the
+// challenge e = poseidon(R.x, R.y, P.x, P.y, msg) modulo the curve order.
+//
+// Several parties sign together the way MuSig2 does, without its key
+// aggregation coefficients since a binding signature verifies against the
+// plain sum of the deltas of the compliance units. Each party shares the
+// commitments R1 and R2 of a pair of nonces, and R = R1 + b * R2 with R1 and
+// R2 the sums of the shared commitments and b a hash of both, the public key
+// and the message. Binding R to the message this way is what keeps the
+// nonces of a party from being chosen against the others.
+//
+// Without the coefficients, a party that chooses its public key after
+// seeing the others', e.g. X - P for the sum P of theirs, can sign alone for
+// the sum. This is only for binding signatures, whose public keys are the
+// deltas the compliance proofs commit to, and never for keys the signers
+// choose themselves.
+pub const SIGNATURE_BYTES: usize = 96;
+// The commitments of a nonce pair, two points as x || y.
+pub const NONCE_COMMITMENT_BYTES: usize = 128;
+
+// The tags of the Poseidon hashes that derive the nonce of a single signer
+// and the binding coefficient, as felts of their ASCII bytes.
+const NONCE_TAG: &[u8] = b"Cairo_SchnorrNonce";
+const BINDING_TAG: &[u8] = b"Cairo_SchnorrBinding";
+
+// A secret nonce pair and its commitments.
+pub type NoncePair = ([Felt; 2], [ProjectivePoint; 2]);
+
+// Sign alone with the deterministic k of RFC 6979. The derivation hashes the
+// message with a Schnorr tag and the public key, so that k differs from the
+// one of an ECDSA signature of the same message with the same key.
+pub fn sign(private_key: &Felt, msg: &Felt) -> Result<Vec<u8>, CairoSignError> {
+    let pub_key = mul_generator(private_key);
+    let pub_key_affine = pub_key
+        .to_affine()
+        .map_err(|_| CairoSignError::SignatureGenerationError("Invalid private key".to_string()))?;
+    let nonce_msg =
+        poseidon_hash_many(&[tag(NONCE_TAG), pub_key_affine.x(), pub_key_affine.y(), *msg]);
+    let k = rfc6979_generate_k(&nonce_msg, private_key, None);
+
+    let commitment = mul_generator(&k);
+    let s = share(private_key, &k, &commitment, &pub_key, msg)?;
+    signature_bytes(&commitment, &s)
+}
+
+// A random nonce pair and its commitments, the commitments being shared with
+// the other signers. The nonces must be used for one partial signature only:
+// never again, and not in several signing sessions at once.
+pub fn nonce() -> NoncePair {
+    let nonces = [random_nonce(), random_nonce()];
+    let commitments = [mul_generator(&nonces[0]), mul_generator(&nonces[1])];
+    (nonces, commitments)
+}
+
+// The sums of the nonce commitments of all signers.
+pub fn nonce_commitment<B: Deref<Target = [u8]>>(
+    commitments: &[B],
+) -> Result<[ProjectivePoint; 2], CairoBindingSigVerifyError> {
+    commitments.iter().try_fold(
+        [ProjectivePoint::identity(), ProjectivePoint::identity()],
+        |[r1, r2], bytes| {
+            if bytes.len() != NONCE_COMMITMENT_BYTES {
+                return Err(CairoBindingSigVerifyError::InputError);
+            }
+            Ok([
+                &r1 + &point_from_bytes(&bytes[0..64])?,
+                &r2 + &point_from_bytes(&bytes[64..128])?,
+            ])
+        },
+    )
+}
+
+// The share of one signer, commitment and pub_key being the sums of the
+// nonce commitments and public keys of all signers.
+pub fn partial_sign(
+    private_key: &Felt,
+    nonces: &[Felt; 2],
+    commitment: &[ProjectivePoint; 2],
+    pub_key: &ProjectivePoint,
+    msg: &Felt,
+) -> Result<Felt, CairoSignError> {
+    let b = binding_coefficient(commitment, pub_key, msg).map_err(|_| {
+        CairoSignError::SignatureGenerationError("Invalid nonce or key".to_string())
+    })?;
+    let nonce = reduce(&(nonces[0].to_bigint() + b.to_bigint() * nonces[1].to_bigint()));
+    let commitment = &commitment[0] + &(&commitment[1] * b);
+    share(private_key, &nonce, &commitment, pub_key, msg)
+}
+
+// The signature of the partial signatures, over the same sums of nonce
+// commitments and public keys.
+pub fn aggregate(
+    commitment: &[ProjectivePoint; 2],
+    pub_key: &ProjectivePoint,
+    msg: &Felt,
+    partial_signatures: &[Felt],
+) -> Result<Vec<u8>, CairoSignError> {
+    let b = binding_coefficient(commitment, pub_key, msg).map_err(|_| {
+        CairoSignError::SignatureGenerationError("Invalid nonce or key".to_string())
+    })?;
+    let commitment = &commitment[0] + &(&commitment[1] * b);
+    let s = reduce(
+        &partial_signatures
+            .iter()
+            .map(|s| s.to_bigint())
+            .sum::<BigInt>(),
+    );
+    signature_bytes(&commitment, &s)
+}
+
+pub fn verify(
+    pub_key: &ProjectivePoint,
+    msg: &Felt,
+    signature: &[u8],
+) -> Result<bool, CairoBindingSigVerifyError> {
+    if signature.len() != SIGNATURE_BYTES {
+        return Err(CairoBindingSigVerifyError::InputError);
+    }
+    let commitment = ProjectivePoint::try_from(point_from_bytes(&signature[0..64])?)
+        .map_err(|_| CairoBindingSigVerifyError::InputError)?;
+    let s =
+        felt_from_bytes(&signature[64..96]).map_err(|_| CairoBindingSigVerifyError::InputError)?;
+    if s.to_bigint() >= EC_ORDER.to_bigint() {
+        return Err(CairoBindingSigVerifyError::InputError);
+    }
+
+    let e = challenge(&commitment, pub_key, msg)?;
+    Ok(mul_generator(&s) == &commitment + &(pub_key * e))
+}
+
+pub fn point_to_bytes(point: &ProjectivePoint) -> Result<Vec<u8>, CairoBindingSigVerifyError> {
+    let point = point
+        .to_affine()
+        .map_err(|_| CairoBindingSigVerifyError::InputError)?;
+    Ok([point.x().to_bytes_be(), point.y().to_bytes_be()].concat())
+}
+
+// s = nonce + e * private_key for the final nonce commitment
+fn share(
+    private_key: &Felt,
+    nonce: &Felt,
+    commitment: &ProjectivePoint,
+    pub_key: &ProjectivePoint,
+    msg: &Felt,
+) -> Result<Felt, CairoSignError> {
+    let e = challenge(commitment, pub_key, msg).map_err(|_| {
+        CairoSignError::SignatureGenerationError("Invalid nonce or key".to_string())
+    })?;
+    Ok(reduce(
+        &(nonce.to_bigint() + e.to_bigint() * private_key.to_bigint()),
+    ))
+}
+
+fn signature_bytes(commitment: &ProjectivePoint, s: &Felt) -> Result<Vec<u8>, CairoSignError> {
+    let mut signature = point_to_bytes(commitment).map_err(|_| {
+        CairoSignError::SignatureGenerationError("Invalid nonce commitment".to_string())
+    })?;
+    signature.extend(s.to_bytes_be());
+    Ok(signature)
+}
+
+fn challenge(
+    commitment: &ProjectivePoint,
+    pub_key: &ProjectivePoint,
+    msg: &Felt,
+) -> Result<Felt, CairoBindingSigVerifyError> {
+    let commitment = affine(commitment)?;
+    let pub_key = affine(pub_key)?;
+    let hash = poseidon_hash_many(&[
+        commitment.x(),
+        commitment.y(),
+        pub_key.x(),
+        pub_key.y(),
+        *msg,
+    ]);
+    Ok(reduce(&hash.to_bigint()))
+}
+
+// b = poseidon(tag, R1.x, R1.y, R2.x, R2.y, P.x, P.y, msg) modulo the curve
+// order
+fn binding_coefficient(
+    commitment: &[ProjectivePoint; 2],
+    pub_key: &ProjectivePoint,
+    msg: &Felt,
+) -> Result<Felt, CairoBindingSigVerifyError> {
+    let r1 = affine(&commitment[0])?;
+    let r2 = affine(&commitment[1])?;
+    let pub_key = affine(pub_key)?;
+    let hash = poseidon_hash_many(&[
+        tag(BINDING_TAG),
+        r1.x(),
+        r1.y(),
+        r2.x(),
+        r2.y(),
+        pub_key.x(),
+        pub_key.y(),
+        *msg,
+    ]);
+    Ok(reduce(&hash.to_bigint()))
+}
+
+fn affine(point: &ProjectivePoint) -> Result<AffinePoint, CairoBindingSigVerifyError> {
+    point
+        .to_affine()
+        .map_err(|_| CairoBindingSigVerifyError::InputError)
+}
+
+fn tag(tag: &[u8]) -> Felt {
+    Felt::from_bytes_be_slice(tag)
+}
+
+fn random_nonce() -> Felt {
+    loop {
+        let nonce = reduce(&Felt::from_bytes_be_slice(&random_felt()).to_bigint());
+        if nonce != Felt::ZERO {
+            return nonce;
+        }
+    }
+}
+
+fn mul_generator(scalar: &Felt) -> ProjectivePoint {
+    let generator = ProjectivePoint::from_affine(GENERATOR.x(), GENERATOR.y())
+        .expect("The generator is on the curve");
+    &generator * *scalar
+}
+
+// The scalar modulo the curve order
+fn reduce(scalar: &BigInt) -> Felt {
+    let (_, bytes) = scalar.mod_floor(&EC_ORDER.to_bigint()).to_bytes_be();
+    Felt::from_bytes_be_slice(&bytes)
+}
+
+#[test]
+fn test_schnorr_aggregate() {
+    let msg = Felt::from(42u64);
+    let private_key = Felt::from(7u64);
+    let pub_key = mul_generator(&private_key);
+
+    let signature = sign(&private_key, &msg).unwrap();
+    assert_eq!(signature.len(), SIGNATURE_BYTES);
+    assert_eq!(sign(&private_key, &msg).unwrap(), signature);
+    assert!(verify(&pub_key, &msg, &signature).unwrap());
+    assert!(!verify(&pub_key, &Felt::ONE, &signature).unwrap());
+    assert!(!verify(&mul_generator(&Felt::TWO), &msg, &signature).unwrap());
+
+    // Two signers whose private keys sum to private_key
+    let private_keys = [Felt::TWO, Felt::from(5u64)];
+    let nonces = [nonce(), nonce()];
+    let commitments: Vec<Vec<u8>> = nonces
+        .iter()
+        .map(|(_, [r1, r2])| [point_to_bytes(r1).unwrap(), point_to_bytes(r2).unwrap()].concat())
+        .collect();
+    let commitment = nonce_commitment(&commitments).unwrap();
+    let partial_signatures: Vec<Felt> = private_keys
+        .iter()
+        .zip(&nonces)
+        .map(|(private_key, (nonces, _))| {
+            partial_sign(private_key, nonces, &commitment, &pub_key, &msg).unwrap()
+        })
+        .collect();
+
+    let signature = aggregate(&commitment, &pub_key, &msg, &partial_signatures).unwrap();
+    assert!(verify(&pub_key, &msg, &signature).unwrap());
+    let signature = aggregate(&commitment, &pub_key, &msg, &partial_signatures[..1]).unwrap();
+    assert!(!verify(&pub_key, &msg, &signature).unwrap());
+
+    // The nonce commitment depends on the message
+    let other = aggregate(&commitment, &pub_key, &Felt::ONE, &partial_signatures).unwrap();
+    assert_ne!(other[0..64], signature[0..64]);
+}
+
+#[test]
+fn test_schnorr_nonce_differs_from_ecdsa_nonce() {
+    let msg = Felt::from(42u64);
+    let private_key = Felt::from(7u64);
+
+    // The x coordinate of the nonce point is r for ECDSA
+    let ecdsa = crate::binding_sig_sign(&private_key, &msg).unwrap();
+    let schnorr = sign(&private_key, &msg).unwrap();
+    assert_ne!(ecdsa[0..32], schnorr[0..32]);
+
+    let ecdsa_nonce = rfc6979_generate_k(&msg, &private_key, None);
+    assert_ne!(
+        point_to_bytes(&mul_generator(&ecdsa_nonce)).unwrap(),
+        schnorr[0..64]
+    );
+}
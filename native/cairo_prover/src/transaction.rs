@@ -0,0 +1,339 @@
+use crate::{
+    binding_private_key, binding_public_key, binding_sig_sign, binding_sig_verify,
+    errors::{CairoVerifyError, TransactionError},
+    get_output,
+    options::CairoProofOptions,
+    outputs::{ComplianceResult, LogicResult},
+    program_hash_from_public_input,
+    utils::{felt_from_bytes, BinaryBytes},
+    verify_proof, COMPLIANCE_PROGRAM_HASH,
+};
+use rayon::prelude::*;
+use rustler::NifStruct;
+use serde::{Deserialize, Serialize};
+use starknet_crypto::poseidon_hash_many;
+use starknet_types_core::felt::Felt;
+use std::collections::HashSet;
+
+// Every encoded transaction starts with the magic header followed by the
+// format version as a little-endian u16. The rest is the bincode encoded
+// TransactionBody.
+pub const TRANSACTION_MAGIC: [u8; 4] = *b"AATX";
+pub const TRANSACTION_FORMAT_VERSION: u16 = 1;
+
+const HEADER_LEN: usize = TRANSACTION_MAGIC.len() + 2;
+
+// A proof with the public input it proves.
+pub type ProofRecord = (BinaryBytes, BinaryBytes);
+
+#[derive(Serialize, Deserialize)]
+struct TransactionBody {
+    compliance_units: Vec<(Vec<u8>, Vec<u8>)>,
+    logic_proofs: Vec<(Vec<u8>, Vec<u8>)>,
+    binding_signature: Vec<u8>,
+    roots: Vec<Vec<u8>>,
+}
+
+// A transaction: the proofs of its compliance units, the proofs of the logics
+// of its resources, its binding signature and the sorted set of the roots
+// its compliance units prove membership in.
+//
+// The binding signature signs the input nullifiers of the compliance units
+// followed by their output commitments, with the sum of their rcv values.
+// Its public key is the sum of their deltas, the sum of the rcv values times
+// the generator when the transaction is balanced.
+#[derive(NifStruct)]
+#[module = "Cairo.Transaction"]
+pub struct Transaction {
+    pub compliance_units: Vec<ProofRecord>,
+    pub logic_proofs: Vec<ProofRecord>,
+    pub binding_signature: BinaryBytes,
+    pub roots: Vec<BinaryBytes>,
+}
+
+impl Transaction {
+    // Sign the proofs with the rcv values of the compliance units.
+    pub fn new(
+        compliance_units: Vec<ProofRecord>,
+        logic_proofs: Vec<ProofRecord>,
+        rcvs: &[BinaryBytes],
+    ) -> Result<Self, TransactionError> {
+        let results = compliance_results(&compliance_units)?;
+        let private_key_segments: Vec<u8> = rcvs.iter().flat_map(|rcv| rcv.0.clone()).collect();
+        let binding_signature = binding_sig_sign(
+            &binding_private_key(&private_key_segments),
+            &binding_message(&results)?,
+        )?;
+        Ok(Self {
+            compliance_units,
+            logic_proofs,
+            binding_signature: binding_signature.into(),
+            roots: roots(&results).into_iter().map(BinaryBytes).collect(),
+        })
+    }
+
+    // The transaction with the proofs of both, signed with the rcv values of
+    // the compliance units of both. Their roots must match their compliance
+    // units and no nullifier or commitment may appear twice. Neither their
+    // proofs nor their binding signatures are checked: a partial transaction
+    // may lack logic proofs and is usually unbalanced, so that its signature
+    // does not verify against its deltas until it is merged.
+    pub fn merge(
+        self,
+        other: Self,
+        private_key_segments: &[BinaryBytes],
+    ) -> Result<Self, TransactionError> {
+        let results = [self.checked_results()?, other.checked_results()?].concat();
+        if let Some(id) = duplicate_resource(&results) {
+            return Err(TransactionError::DuplicateResource(id));
+        }
+
+        let mut compliance_units = self.compliance_units;
+        compliance_units.extend(other.compliance_units);
+        let mut logic_proofs = self.logic_proofs;
+        logic_proofs.extend(other.logic_proofs);
+        Self::new(compliance_units, logic_proofs, private_key_segments)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, TransactionError> {
+        let records = |proofs: &[ProofRecord]| {
+            proofs
+                .iter()
+                .map(|(proof, public_input)| (proof.0.clone(), public_input.0.clone()))
+                .collect()
+        };
+        let body = TransactionBody {
+            compliance_units: records(&self.compliance_units),
+            logic_proofs: records(&self.logic_proofs),
+            binding_signature: self.binding_signature.0.clone(),
+            roots: self.roots.iter().map(|root| root.0.clone()).collect(),
+        };
+        let body = bincode::serde::encode_to_vec(&body, bincode::config::standard())
+            .map_err(|e| TransactionError::EncodingError(e.to_string()))?;
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + body.len());
+        bytes.extend_from_slice(&TRANSACTION_MAGIC);
+        bytes.extend_from_slice(&TRANSACTION_FORMAT_VERSION.to_le_bytes());
+        bytes.extend(body);
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TransactionError> {
+        if bytes.len() < HEADER_LEN || bytes[0..TRANSACTION_MAGIC.len()] != TRANSACTION_MAGIC {
+            return Err(TransactionError::DecodingError(
+                "invalid transaction header".to_string(),
+            ));
+        }
+        let format_version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if format_version != TRANSACTION_FORMAT_VERSION {
+            return Err(TransactionError::DecodingError(format!(
+                "format version {}, expected {}",
+                format_version, TRANSACTION_FORMAT_VERSION
+            )));
+        }
+
+        let (body, consumed): (TransactionBody, usize) =
+            bincode::serde::decode_from_slice(&bytes[HEADER_LEN..], bincode::config::standard())
+                .map_err(|e| TransactionError::DecodingError(e.to_string()))?;
+        if consumed != bytes.len() - HEADER_LEN {
+            return Err(TransactionError::DecodingError(format!(
+                "{} trailing bytes",
+                bytes.len() - HEADER_LEN - consumed
+            )));
+        }
+        let records = |proofs: Vec<(Vec<u8>, Vec<u8>)>| {
+            proofs
+                .into_iter()
+                .map(|(proof, public_input)| (BinaryBytes(proof), BinaryBytes(public_input)))
+                .collect()
+        };
+        Ok(Self {
+            compliance_units: records(body.compliance_units),
+            logic_proofs: records(body.logic_proofs),
+            binding_signature: BinaryBytes(body.binding_signature),
+            roots: body.roots.into_iter().map(BinaryBytes).collect(),
+        })
+    }
+
+    // Check the proofs, that the compliance units are proofs of the
+    // compliance program, that no resource of the compliance units appears
+    // twice and has a proof of its logic, and the binding signature.
+    pub fn verify(&self, options: Option<&CairoProofOptions>) -> Result<(), TransactionError> {
+        verify_proofs(&self.compliance_units, options)
+            .map_err(|(index, e)| TransactionError::InvalidComplianceProof(index, e))?;
+        verify_proofs(&self.logic_proofs, options)
+            .map_err(|(index, e)| TransactionError::InvalidLogicProof(index, e))?;
+
+        for (index, (_, public_input)) in self.compliance_units.iter().enumerate() {
+            if program_hash_from_public_input(&public_input.0)? != *COMPLIANCE_PROGRAM_HASH {
+                return Err(TransactionError::ComplianceProgramMismatch(index));
+            }
+        }
+
+        let results = compliance_results(&self.compliance_units)?;
+        self.check_roots(&results)?;
+        if let Some(id) = duplicate_resource(&results) {
+            return Err(TransactionError::DuplicateResource(id));
+        }
+
+        let logic_results = self
+            .logic_proofs
+            .iter()
+            .map(|(_, public_input)| {
+                let result = get_output(&public_input.0).and_then(LogicResult::from_output)?;
+                let logic = program_hash_from_public_input(&public_input.0)?;
+                Ok((result.self_resource_id.0, logic))
+            })
+            .collect::<Result<Vec<_>, TransactionError>>()?;
+
+        // The resources of the compliance units with their logic
+        let resources = results
+            .iter()
+            .map(|result| (&result.input_nullifier, &result.input_logic))
+            .chain(
+                results
+                    .iter()
+                    .map(|result| (&result.output_cm, &result.output_logic)),
+            );
+
+        let mut proven = vec![false; logic_results.len()];
+        for (id, logic) in resources {
+            let index = logic_results
+                .iter()
+                .position(|(self_resource_id, _)| *self_resource_id == id.0)
+                .ok_or_else(|| TransactionError::MissingLogicProof(id.0.clone()))?;
+            if logic_results[index].1 != felt_from_bytes(&logic.0)? {
+                return Err(TransactionError::LogicMismatch(index));
+            }
+            proven[index] = true;
+        }
+        if let Some(index) = proven.iter().position(|proven| !proven) {
+            return Err(TransactionError::UnexpectedLogicProof(index));
+        }
+
+        let deltas: Vec<Vec<u8>> = results
+            .iter()
+            .map(|result| [result.delta_x.0.as_slice(), &result.delta_y.0].concat())
+            .collect();
+        let pub_key = binding_public_key(&deltas)?;
+        let msg = binding_message(&results)?;
+        if !binding_sig_verify(&pub_key, &msg, &self.binding_signature.0)? {
+            return Err(TransactionError::InvalidBindingSignature);
+        }
+        Ok(())
+    }
+
+    // The compliance results, once checked against the roots.
+    fn checked_results(&self) -> Result<Vec<ComplianceResult>, TransactionError> {
+        let results = compliance_results(&self.compliance_units)?;
+        self.check_roots(&results)?;
+        Ok(results)
+    }
+
+    fn check_roots(&self, results: &[ComplianceResult]) -> Result<(), TransactionError> {
+        if !self
+            .roots
+            .iter()
+            .map(|root| &root.0)
+            .eq(roots(results).iter())
+        {
+            return Err(TransactionError::RootsMismatch);
+        }
+        Ok(())
+    }
+}
+
+fn compliance_results(
+    compliance_units: &[ProofRecord],
+) -> Result<Vec<ComplianceResult>, TransactionError> {
+    compliance_units
+        .iter()
+        .map(|(_, public_input)| {
+            get_output(&public_input.0)
+                .and_then(ComplianceResult::from_output)
+                .map_err(TransactionError::from)
+        })
+        .collect()
+}
+
+// A nullifier or commitment that appears twice in the compliance units.
+fn duplicate_resource(results: &[ComplianceResult]) -> Option<Vec<u8>> {
+    let mut ids = HashSet::new();
+    results
+        .iter()
+        .map(|result| &result.input_nullifier.0)
+        .chain(results.iter().map(|result| &result.output_cm.0))
+        .find(|id| !ids.insert(*id))
+        .cloned()
+}
+
+// The distinct roots of the compliance units, sorted.
+fn roots(results: &[ComplianceResult]) -> Vec<Vec<u8>> {
+    let mut roots: Vec<Vec<u8>> = results.iter().map(|result| result.root.0.clone()).collect();
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+// The digest of the input nullifiers followed by the output commitments.
+fn binding_message(results: &[ComplianceResult]) -> Result<Felt, TransactionError> {
+    let messages = results
+        .iter()
+        .map(|result| felt_from_bytes(&result.input_nullifier.0))
+        .chain(
+            results
+                .iter()
+                .map(|result| felt_from_bytes(&result.output_cm.0)),
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(poseidon_hash_many(&messages))
+}
+
+// Verify the proofs in parallel, returning the index of the first failure.
+fn verify_proofs(
+    proofs: &[ProofRecord],
+    options: Option<&CairoProofOptions>,
+) -> Result<(), (usize, Option<CairoVerifyError>)> {
+    let results: Vec<_> = proofs
+        .par_iter()
+        .map(|(proof, public_input)| verify_proof(&proof.0, &public_input.0, options))
+        .collect();
+    match results
+        .into_iter()
+        .enumerate()
+        .find(|(_, result)| !matches!(result, Ok(true)))
+    {
+        Some((index, Ok(_))) => Err((index, None)),
+        Some((index, Err(e))) => Err((index, Some(e))),
+        None => Ok(()),
+    }
+}
+
+#[test]
+fn test_transaction_bytes() {
+    let transaction = Transaction {
+        compliance_units: vec![(vec![1, 2].into(), vec![3].into())],
+        logic_proofs: vec![],
+        binding_signature: vec![4; 64].into(),
+        roots: vec![vec![5; 32].into()],
+    };
+    let bytes = transaction.to_bytes().unwrap();
+    assert_eq!(&bytes[0..6], b"AATX\x01\x00");
+
+    let decoded = Transaction::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.compliance_units[0].0 .0, vec![1, 2]);
+    assert_eq!(decoded.roots[0].0, vec![5; 32]);
+    assert_eq!(decoded.to_bytes().unwrap(), bytes);
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(
+        Transaction::from_bytes(&trailing),
+        Err(TransactionError::DecodingError(_))
+    ));
+
+    assert!(matches!(
+        Transaction::from_bytes(b"AATX\x02\x00"),
+        Err(TransactionError::DecodingError(_))
+    ));
+}
@@ -1,14 +1,64 @@
+use crate::errors::{allocation_error, FeltError};
 use rand::{thread_rng, RngCore};
+use rustler::{types::atom, Binary, Decoder, Encoder, Env, Error, NifResult, OwnedBinary, Term};
 use starknet_types_core::felt::Felt;
+use std::ops::Deref;
 
-pub fn felt_to_string(felt: &Vec<u8>) -> String {
-    assert_eq!(felt.len(), 32, "The felt size is not 32 bytes");
-    Felt::from_bytes_be(
-        felt.as_slice()
-            .try_into()
-            .expect("Slice with incorrect length"),
-    )
-    .to_hex_string()
+// Bytes returned to Elixir as a binary rather than a list of bytes.
+#[derive(Clone)]
+pub struct BinaryBytes(pub Vec<u8>);
+
+impl BinaryBytes {
+    pub fn to_binary<'a>(&self, env: Env<'a>) -> NifResult<Binary<'a>> {
+        let mut binary = OwnedBinary::new(self.0.len())
+            .ok_or_else(|| Error::Term(Box::new(allocation_error())))?;
+        binary.as_mut_slice().copy_from_slice(&self.0);
+        Ok(binary.release(env))
+    }
+}
+
+// Encoding cannot fail, a binary that cannot be allocated is encoded as
+// {:error, :allocation_error} in its place rather than panicking in the NIF.
+impl Encoder for BinaryBytes {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        match self.to_binary(env) {
+            Ok(binary) => binary.encode(env),
+            Err(_) => (atom::error(), allocation_error()).encode(env),
+        }
+    }
+}
+
+impl<'a> Decoder<'a> for BinaryBytes {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        Ok(Self(term.decode::<Binary>()?.as_slice().to_vec()))
+    }
+}
+
+impl From<Vec<u8>> for BinaryBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+// Decode 32 big-endian bytes, rejecting values that are not below the field
+// prime instead of reducing them.
+pub fn felt_from_bytes(bytes: &[u8]) -> Result<Felt, FeltError> {
+    let bytes: &[u8; 32] = bytes
+        .try_into()
+        .map_err(|_| FeltError::InvalidLength(bytes.len()))?;
+    let felt = Felt::from_bytes_be(bytes);
+    if felt.to_bytes_be() != *bytes {
+        return Err(FeltError::OutOfRange);
+    }
+    Ok(felt)
+}
+
+pub fn felts_from_bytes<B: Deref<Target = [u8]>>(inputs: &[B]) -> Result<Vec<Felt>, FeltError> {
+    inputs.iter().map(|bytes| felt_from_bytes(bytes)).collect()
+}
+
+pub fn felt_to_string(felt: &[u8]) -> Result<String, FeltError> {
+    Ok(felt_from_bytes(felt)?.to_hex_string())
 }
 
 pub fn random_felt() -> Vec<u8> {
@@ -18,3 +68,19 @@ pub fn random_felt() -> Vec<u8> {
     let felt = Felt::from_bytes_be_slice(&felt);
     felt.to_bytes_be().to_vec()
 }
+
+#[test]
+fn test_felt_from_bytes() {
+    assert!(matches!(
+        felt_from_bytes(&[1u8; 31]),
+        Err(FeltError::InvalidLength(31))
+    ));
+    assert!(matches!(
+        felt_from_bytes(&[0xffu8; 32]),
+        Err(FeltError::OutOfRange)
+    ));
+    assert_eq!(
+        felt_from_bytes(&Felt::MAX.to_bytes_be()).unwrap(),
+        Felt::MAX
+    );
+}
@@ -1,10 +1,18 @@
 use rustler::{Encoder, Env, Term};
 
+rustler::atoms! {
+    invalid_program_content,
+    invalid_input_json,
+    runtime_error,
+    allocation_error,
+}
+
 #[derive(Debug)]
 pub(crate) enum CairoVMError {
     InvalidProgramContent,
     InvalidInputJSON,
     RuntimeError(String),
+    AllocationError,
 }
 
 impl std::fmt::Display for CairoVMError {
@@ -13,12 +21,18 @@ impl std::fmt::Display for CairoVMError {
             CairoVMError::InvalidProgramContent => write!(f, "Invalid program content"),
             CairoVMError::InvalidInputJSON => write!(f, "Invalid input JSON"),
             CairoVMError::RuntimeError(msg) => write!(f, "Runtime error: {}", msg),
+            CairoVMError::AllocationError => write!(f, "Failed to allocate binary"),
         }
     }
 }
 
 impl Encoder for CairoVMError {
     fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        self.to_string().encode(env)
+        match self {
+            CairoVMError::InvalidProgramContent => invalid_program_content().encode(env),
+            CairoVMError::InvalidInputJSON => invalid_input_json().encode(env),
+            CairoVMError::RuntimeError(msg) => (runtime_error(), msg).encode(env),
+            CairoVMError::AllocationError => allocation_error().encode(env),
+        }
     }
 }
@@ -2,15 +2,16 @@ mod errors;
 
 use crate::errors::CairoVMError;
 use juvix_cairo_vm::{anoma_cairo_vm_runner, program_input::ProgramInput};
-use rustler::{Error, NifResult};
+use rustler::{Binary, Env, Error, NifResult, OwnedBinary};
 use serde_json::Value;
 use std::collections::HashMap;
 
 #[rustler::nif(schedule = "DirtyCpu")]
-fn cairo_vm_runner(
+fn cairo_vm_runner<'a>(
+    env: Env<'a>,
     program_content: String,
     inputs: String,
-) -> NifResult<(String, Vec<u8>, Vec<u8>, Vec<u8>)> {
+) -> NifResult<(String, Binary<'a>, Binary<'a>, Binary<'a>)> {
     // Validate program content
     serde_json::from_str::<Value>(&program_content)
         .map_err(|_| Error::Term(Box::new(CairoVMError::InvalidProgramContent)))?;
@@ -23,8 +24,24 @@ fn cairo_vm_runner(
             .map_err(|_| Error::Term(Box::new(CairoVMError::InvalidInputJSON)))?
     };
 
-    anoma_cairo_vm_runner(&program_content.as_bytes(), program_input)
-        .map_err(|e| Error::Term(Box::new(CairoVMError::RuntimeError(e.to_string()))))
+    let (output, trace, memory, public_input) =
+        anoma_cairo_vm_runner(&program_content.as_bytes(), program_input)
+            .map_err(|e| Error::Term(Box::new(CairoVMError::RuntimeError(e.to_string()))))?;
+
+    Ok((
+        output,
+        to_binary(env, &trace)?,
+        to_binary(env, &memory)?,
+        to_binary(env, &public_input)?,
+    ))
+}
+
+// Return bytes to Elixir as a binary rather than a list of bytes.
+fn to_binary<'a>(env: Env<'a>, bytes: &[u8]) -> NifResult<Binary<'a>> {
+    let mut binary = OwnedBinary::new(bytes.len())
+        .ok_or_else(|| Error::Term(Box::new(CairoVMError::AllocationError)))?;
+    binary.as_mut_slice().copy_from_slice(bytes);
+    Ok(binary.release(env))
 }
 
 rustler::init!("Elixir.Cairo.CairoVM", [cairo_vm_runner]);
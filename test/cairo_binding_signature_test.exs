@@ -0,0 +1,112 @@
+defmodule BindingSignatureTest do
+  use ExUnit.Case
+
+  doctest Cairo.CairoProver
+
+  test "cairo_binding_signature_test" do
+    # Byte lists are given back as lists
+    priv_key_1 = Cairo.random_felt()
+    priv_key_2 = Cairo.random_felt()
+    assert length(priv_key_1) == 32
+
+    pub_keys =
+      [priv_key_1, priv_key_2]
+      |> Enum.map(fn x -> Cairo.get_public_key(x) end)
+
+    msg = [Cairo.random_felt(), Cairo.random_felt()]
+
+    # Sign and verify
+    signature = (priv_key_1 ++ priv_key_2) |> Cairo.sign(msg)
+    assert true = Cairo.sig_verify(pub_keys, msg, signature)
+
+    # The nonce is derived from the private key and the message
+    assert Cairo.sign(priv_key_1 ++ priv_key_2, msg) == signature
+    refute Cairo.sign(priv_key_1 ++ priv_key_2, Enum.reverse(msg)) ==
+             signature
+  end
+
+  test "recoverable binding signatures" do
+    priv_keys = [Cairo.random_felt_binary(), Cairo.random_felt_binary()]
+    pub_keys = Enum.map(priv_keys, &Cairo.get_public_key/1)
+    msg = [Cairo.random_felt_binary(), Cairo.random_felt_binary()]
+
+    signature = priv_keys |> Enum.join() |> Cairo.sign_recoverable(msg)
+    assert byte_size(signature) == 65
+    assert Cairo.sig_verify(pub_keys, msg, signature)
+    refute Cairo.sig_verify(pub_keys, Enum.reverse(msg), signature)
+
+    # The recovered key is the x coordinate of the public key
+    [priv_key | _] = priv_keys
+    signature = Cairo.sign_recoverable(priv_key, msg)
+    pub_key = Cairo.get_public_key(priv_key)
+    assert Cairo.recover_public_key(msg, signature) ==
+             binary_part(pub_key, 0, 32)
+
+    assert {:error, :input_error} =
+             Cairo.recover_public_key(msg, binary_part(signature, 0, 64))
+  end
+
+  test "schnorr binding signatures aggregate" do
+    priv_keys = [Cairo.random_felt_binary(), Cairo.random_felt_binary()]
+    pub_keys = Enum.map(priv_keys, &Cairo.get_public_key/1)
+    msg = [Cairo.random_felt_binary(), Cairo.random_felt_binary()]
+
+    signature = priv_keys |> Enum.join() |> Cairo.schnorr_sign(msg)
+    assert byte_size(signature) == 96
+    assert Cairo.schnorr_verify(pub_keys, msg, signature)
+    refute Cairo.schnorr_verify(pub_keys, Enum.reverse(msg), signature)
+
+    # Each signer only knows its own private key
+    nonces = Enum.map(priv_keys, fn _ -> Cairo.schnorr_nonce() end)
+    commitments = Enum.map(nonces, &elem(&1, 1))
+    assert [{<<_::512>>, <<_::1024>>} | _] = nonces
+
+    partial_signatures =
+      Enum.zip_with(priv_keys, nonces, fn priv_key, {nonce, _} ->
+        Cairo.schnorr_partial_sign(
+          priv_key,
+          nonce,
+          commitments,
+          pub_keys,
+          msg
+        )
+      end)
+
+    signature =
+      Cairo.schnorr_aggregate(commitments, pub_keys, msg, partial_signatures)
+
+    assert Cairo.schnorr_verify(pub_keys, msg, signature)
+    refute Cairo.schnorr_verify(tl(pub_keys), msg, signature)
+
+    # The nonce commitment depends on the messages
+    other =
+      Cairo.schnorr_aggregate(
+        commitments,
+        pub_keys,
+        Enum.reverse(msg),
+        partial_signatures
+      )
+
+    refute binary_part(other, 0, 64) == binary_part(signature, 0, 64)
+
+    [priv_key | _] = priv_keys
+
+    assert {:error, {:signature_generation_error, _}} =
+             Cairo.schnorr_partial_sign(
+               priv_key,
+               <<0::256>>,
+               commitments,
+               pub_keys,
+               msg
+             )
+  end
+
+  test "schnorr and ECDSA nonces differ for the same key and messages" do
+    priv_key = Cairo.random_felt_binary()
+    msg = [Cairo.random_felt_binary()]
+
+    # The x coordinate of the nonce point of both
+    assert binary_part(Cairo.sign(priv_key, msg), 0, 32) !=
+             binary_part(Cairo.schnorr_sign(priv_key, msg), 0, 32)
+  end
+end
@@ -7,10 +7,14 @@ defmodule CairoComplianceTest do
   test "compliance_circuit" do
     {:ok, program} = File.read("./native/cairo_vm/compliance.json")
     # {:ok, input} = File.read("./native/cairo_vm/compliance_input.json")
-    input_resource = List.duplicate(1, 225)
+    input_nf_key = Cairo.random_felt()
+    # The npk of the input resource is derived from its nf_key
+    input_resource =
+      List.duplicate(1, 160) ++
+        Cairo.resource_npk(input_nf_key) ++ List.duplicate(1, 33)
+
     output_resource = List.duplicate(2, 225)
     path = List.duplicate(Cairo.random_felt(), 32)
-    input_nf_key = Cairo.random_felt()
     eph_root = Cairo.random_felt()
     rcv = Cairo.random_felt()
 
@@ -35,7 +39,35 @@ defmodule CairoComplianceTest do
     {proof, public_input} = Cairo.prove(trace, memory, public_inputs)
     assert true = Cairo.verify(proof, public_input)
 
-    Cairo.get_output(public_input)
+    output = Cairo.get_output(public_input)
+
+    result = Cairo.get_compliance_output(public_input)
+
+    assert output == [
+             result.input_nullifier,
+             result.output_cm,
+             result.root,
+             result.delta_x,
+             result.delta_y,
+             result.input_logic,
+             result.output_logic
+           ]
+
+    assert result.input_logic == :binary.copy(<<1>>, 32)
+    assert result.output_logic == :binary.copy(<<2>>, 32)
+    # The input resource is ephemeral
+    assert result.root == :binary.list_to_binary(eph_root)
+
+    assert {:error, {:invalid_output_length, %{expected: 2, actual: 7}}} =
+             Cairo.get_logic_output(public_input)
+
+    # Byte lists give the result fields as byte lists
+    list_result =
+      Cairo.get_compliance_output(:binary.bin_to_list(public_input))
+
+    assert %Cairo.ComplianceResult{} = list_result
+    assert list_result.root == :binary.bin_to_list(result.root)
+    assert list_result.delta_x == :binary.bin_to_list(result.delta_x)
 
     # Get program hash
     _program_hash =
@@ -43,4 +75,106 @@ defmodule CairoComplianceTest do
 
     # IO.inspect(program_hash)
   end
+
+  test "compliance input JSON round trip" do
+    input_nf_key = Cairo.CairoProver.cairo_random_felt()
+    rcv = Cairo.CairoProver.cairo_random_felt()
+
+    input_resource =
+      :binary.copy(<<1>>, 160) <>
+        Cairo.resource_npk(input_nf_key) <> :binary.copy(<<1>>, 33)
+
+    json =
+      Cairo.generate_compliance_input_json(%{
+        input_resource: input_resource,
+        output_resource: :binary.copy(<<2>>, 225),
+        merkle_path: List.duplicate(<<0::256>>, 32),
+        position: 5,
+        input_nf_key: input_nf_key,
+        eph_root: <<0::256>>,
+        rcv: rcv
+      })
+
+    parsed = Cairo.parse_compliance_input_json(json)
+    assert Cairo.encode_compliance_input_json(parsed) == json
+
+    assert parsed.input.npk == Cairo.resource_npk(input_nf_key)
+    assert parsed.input.eph
+    assert parsed.output.logic == :binary.copy(<<2>>, 32)
+    assert parsed.input_nf_key == input_nf_key
+    assert parsed.rcv == rcv
+
+    assert Enum.map(parsed.merkle_path, &elem(&1, 1)) ==
+             [true, false, true | List.duplicate(false, 29)]
+
+    modified = %{parsed | rcv: <<0::256>>}
+
+    assert modified
+           |> Cairo.encode_compliance_input_json()
+           |> Cairo.parse_compliance_input_json()
+           |> Map.delete(:source) == Map.delete(modified, :source)
+  end
+
+  test "compliance input JSON round trips byte for byte" do
+    {:ok, file} = File.read("./native/cairo_vm/compliance_input.json")
+    parsed = Cairo.parse_compliance_input_json(file)
+
+    # The stored input is pretty-printed, it is encoded back as is
+    assert parsed.source == file
+    assert Cairo.encode_compliance_input_json(parsed) == file
+
+    # Unknown keys and non-canonical felts are kept
+    extended =
+      file
+      |> String.replace("{", ~S({"note": "unused",), global: false)
+      |> String.replace(~s("0x12"), ~s("0x012"), global: false)
+
+    assert extended
+           |> Cairo.parse_compliance_input_json()
+           |> Cairo.encode_compliance_input_json() == extended
+
+    # Changed fields are written in place, without a source the JSON is
+    # compact
+    json = Cairo.encode_compliance_input_json(%{parsed | rcv: <<2::256>>})
+    assert json == String.replace(file, ~s("rcv": "0x3"), ~s("rcv": "0x2"))
+
+    assert Cairo.encode_compliance_input_json(Map.delete(parsed, :source)) ==
+             String.replace(file, ~r/\s/, "")
+  end
+
+  test "compliance units are padded with ephemeral resources" do
+    {:ok, program} = File.read("./native/cairo_vm/compliance.json")
+    nf_key = Cairo.CairoProver.cairo_random_felt()
+
+    input = %{
+      resource:
+        :binary.copy(<<1>>, 160) <>
+          Cairo.resource_npk(nf_key) <> :binary.copy(<<1>>, 33),
+      nf_key: nf_key,
+      merkle_path: List.duplicate(<<0::256>>, 32),
+      position: 0
+    }
+
+    outputs = [:binary.copy(<<2>>, 225), :binary.copy(<<3>>, 225)]
+
+    [{_json, _rcv, %{input: nil, output: nil}}, {json, rcv, padding}] =
+      Cairo.generate_compliance_units_json([input], outputs)
+
+    assert %{input: {padding_input, padding_nf_key}, output: nil} = padding
+
+    parsed = Cairo.parse_compliance_input_json(json)
+    assert parsed.rcv == rcv
+    assert parsed.input_nf_key == padding_nf_key
+    assert parsed.input.npk == Cairo.resource_npk(padding_nf_key)
+    assert binary_part(padding_input, 128, 32) == parsed.input.nonce
+    assert parsed.input.eph
+    assert parsed.input.quantity == <<0::256>>
+    assert parsed.output.logic == :binary.copy(<<3>>, 32)
+
+    {_output, trace, memory, public_inputs} =
+      Cairo.cairo_vm_runner(program, json)
+
+    {_proof, public_input} = Cairo.prove(trace, memory, public_inputs)
+    assert Cairo.get_compliance_output(public_input).root == Cairo.eph_root()
+  end
 end
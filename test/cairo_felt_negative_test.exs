@@ -0,0 +1,167 @@
+defmodule FeltNegativeTest do
+  use ExUnit.Case
+
+  doctest Cairo.CairoProver
+
+  # The field prime 2^251 + 17 * 2^192 + 1, the smallest 32 byte value
+  # that is not a felt
+  @prime <<0x08, 0::48, 0x11, 0::184, 1>>
+
+  setup_all do
+    [felt: Cairo.CairoProver.cairo_random_felt()]
+  end
+
+  test "poseidon_single with too long input" do
+    assert {:error, {:invalid_felt_length, 33}} =
+             Cairo.poseidon_single(<<0::size(33)-unit(8)>>)
+  end
+
+  test "poseidon with wrong length inputs", context do
+    assert {:error, {:invalid_felt_length, 31}} =
+             Cairo.poseidon(<<0::size(31)-unit(8)>>, context.felt)
+
+    assert {:error, {:invalid_felt_length, 0}} =
+             Cairo.poseidon(context.felt, [])
+  end
+
+  test "poseidon with input out of the field", context do
+    assert {:error, :felt_out_of_range} = Cairo.poseidon(@prime, context.felt)
+  end
+
+  test "poseidon_many with an invalid input", context do
+    assert {:error, {:invalid_felt_length, 3}} =
+             Cairo.poseidon_many([context.felt, <<1, 2, 3>>])
+
+    assert {:error, :felt_out_of_range} =
+             Cairo.poseidon_many([context.felt, @prime])
+  end
+
+  test "sign with invalid messages", context do
+    assert {:error, {:invalid_felt_length, 16}} =
+             Cairo.sign(context.felt, [<<0::size(16)-unit(8)>>])
+
+    assert {:error, :felt_out_of_range} = Cairo.sign(context.felt, [@prime])
+  end
+
+  test "sig_verify with malformed keys and signature", context do
+    pub_key = Cairo.get_public_key(context.felt)
+    signature = Cairo.sign(context.felt, [context.felt])
+
+    assert {:error, :input_error} =
+             Cairo.sig_verify([<<1, 2, 3>>], [context.felt], signature)
+
+    assert {:error, :input_error} =
+             Cairo.sig_verify([pub_key], [context.felt], <<1, 2, 3>>)
+  end
+
+  test "felt_to_string with invalid felts" do
+    assert {:error, {:invalid_felt_length, 2}} = Cairo.felt_to_string([1, 2])
+    assert {:error, :felt_out_of_range} = Cairo.felt_to_string(@prime)
+  end
+
+  test "get_program_hash with invalid public input" do
+    assert {:error, {:decoding_error, _detail}} =
+             Cairo.get_program_hash(<<>>)
+  end
+
+  test "get_program_hash_from_json with invalid programs" do
+    assert {:error, :invalid_program_content} =
+             Cairo.get_program_hash_from_json("This is not valid JSON")
+
+    assert {:error, {:invalid_program_data, "0xzz"}} =
+             Cairo.get_program_hash_from_json(~S({"data": ["0x1", "0xzz"]}))
+  end
+
+  test "generate_compliance_input_json with invalid resources", context do
+    resource = :binary.copy(<<1>>, 225)
+    path = List.duplicate(context.felt, 32)
+
+    assert {:error, {:invalid_resource_length, 224}} =
+             Cairo.generate_compliance_input_json(
+               binary_part(resource, 0, 224),
+               resource,
+               path,
+               0,
+               context.felt,
+               context.felt,
+               context.felt
+             )
+
+    assert {:error, :felt_out_of_range} =
+             Cairo.generate_compliance_input_json(
+               resource,
+               @prime <> binary_part(resource, 32, 193),
+               path,
+               0,
+               context.felt,
+               context.felt,
+               context.felt
+             )
+  end
+
+  test "generate_compliance_input_json with invalid inputs", context do
+    input = compliance_input(context)
+
+    assert is_binary(Cairo.generate_compliance_input_json(input))
+
+    assert {:error, {:missing_fields, [:rcv]}} =
+             Cairo.generate_compliance_input_json(Map.delete(input, :rcv))
+
+    assert {:error, :invalid_nullifier_key} =
+             Cairo.generate_compliance_input_json(%{
+               input
+               | input_nf_key: <<0::256>>
+             })
+
+    assert {:error, {:invalid_path_length, 31}} =
+             Cairo.generate_compliance_input_json(%{
+               input
+               | merkle_path: tl(input.merkle_path)
+             })
+
+    assert {:error, {:invalid_position, 4_294_967_296}} =
+             Cairo.generate_compliance_input_json(%{
+               input
+               | position: 4_294_967_296
+             })
+
+    assert {:error, {:invalid_felt_length, 3}} =
+             Cairo.generate_compliance_input_json(%{
+               input
+               | merkle_path: [<<1, 2, 3>> | tl(input.merkle_path)]
+             })
+  end
+
+  test "parse_compliance_input_json with invalid JSON", context do
+    assert {:error, {:invalid_json, _detail}} =
+             Cairo.parse_compliance_input_json("{}")
+
+    json = Cairo.generate_compliance_input_json(compliance_input(context))
+
+    # The field prime
+    p = "0x800000000000011000000000000000000000000000000000000000000000001"
+
+    assert {:error, {:invalid_felt_string, ^p}} =
+             json
+             |> String.replace(~s("rcv":"), ~s("rcv":"#{p}", "ignored":"))
+             |> Cairo.parse_compliance_input_json()
+  end
+
+  defp compliance_input(context) do
+    nf_key = context.felt
+
+    input_resource =
+      :binary.copy(<<1>>, 160) <>
+        Cairo.resource_npk(nf_key) <> :binary.copy(<<1>>, 33)
+
+    %{
+      input_resource: input_resource,
+      output_resource: :binary.copy(<<2>>, 225),
+      merkle_path: List.duplicate(context.felt, 32),
+      position: 0,
+      input_nf_key: nf_key,
+      eph_root: context.felt,
+      rcv: context.felt
+    }
+  end
+end
@@ -21,7 +21,22 @@ defmodule CairoResourceLogicTest do
     {proof, public_input} = Cairo.prove(trace, memory, public_inputs)
     assert true = Cairo.verify(proof, public_input)
 
-    _output = Cairo.get_output(public_input)
+    output = Cairo.get_output(public_input)
+
+    %Cairo.LogicResult{self_resource_id: self_resource_id, root: root} =
+      Cairo.get_logic_output(public_input)
+
+    assert output == [self_resource_id, root]
+
+    # Byte lists give the result fields as byte lists
+    assert %Cairo.LogicResult{self_resource_id: list_id, root: list_root} =
+             Cairo.get_logic_output(:binary.bin_to_list(public_input))
+
+    assert list_id == :binary.bin_to_list(self_resource_id)
+    assert list_root == :binary.bin_to_list(root)
+
+    assert {:error, {:invalid_output_length, %{expected: 7, actual: 2}}} =
+             Cairo.get_compliance_output(public_input)
 
     # Get program hash
     _program_hash =
@@ -30,4 +45,22 @@ defmodule CairoResourceLogicTest do
     # IO.inspect(program_hash)
     # IO.inspect(program_hash |> Cairo.felt_to_string())
   end
+
+  test "builtin segments the prover cannot constrain are left out" do
+    {:ok, program} =
+      File.read("./native/cairo_vm/trivial_resource_logic.json")
+
+    {:ok, input} =
+      File.read("./native/cairo_vm/trivial_resource_logic_input.json")
+
+    {_output, trace, memory, public_inputs} =
+      Cairo.cairo_vm_runner(program, input)
+
+    {_proof, public_input} = Cairo.prove(trace, memory, public_inputs)
+
+    assert Cairo.public_input_to_json(public_inputs) =~ ~s("type": "poseidon")
+
+    refute Cairo.public_input_to_json(public_input, :bincode) =~
+             ~s("type": "poseidon")
+  end
 end
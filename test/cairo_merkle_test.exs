@@ -0,0 +1,124 @@
+defmodule CairoMerkleTest do
+  use ExUnit.Case
+
+  doctest Cairo.CairoProver
+
+  defp felt, do: Cairo.CairoProver.cairo_random_felt()
+
+  defp resource(npk, eph) do
+    quantity = <<0::248, 5>>
+    felt() <> felt() <> quantity <> felt() <> felt() <> npk <> felt() <> eph
+  end
+
+  test "merkle tree paths verify against the root" do
+    tree = Cairo.merkle_tree_new()
+    empty_root = Cairo.merkle_tree_root(tree)
+    commitments = for _ <- 1..5, do: felt()
+
+    positions = Enum.map(commitments, &Cairo.merkle_tree_append(tree, &1))
+    assert positions == [0, 1, 2, 3, 4]
+    assert Cairo.merkle_tree_size(tree) == 5
+
+    root = Cairo.merkle_tree_root(tree)
+    assert Cairo.merkle_tree_known_root?(tree, root)
+    assert Cairo.merkle_tree_known_root?(tree, empty_root)
+    refute Cairo.merkle_tree_known_root?(tree, felt())
+
+    for {commitment, position} <- Enum.zip(commitments, positions) do
+      path = Cairo.merkle_tree_path(tree, position)
+      assert length(path) == 32
+      assert Cairo.merkle_verify(root, commitment, path, position)
+      refute Cairo.merkle_verify(root, felt(), path, position)
+    end
+
+    path = Cairo.merkle_tree_path(tree, 0)
+    refute Cairo.merkle_verify(root, hd(commitments), path, 1)
+
+    assert {:error, {:invalid_position, 5}} =
+             Cairo.merkle_tree_path(tree, 5)
+
+    assert {:error, {:invalid_felt_length, 2}} =
+             Cairo.merkle_tree_append(tree, <<1, 2>>)
+  end
+
+  test "merkle trees remember the last 100 roots and eph_root" do
+    tree = Cairo.merkle_tree_new()
+    Cairo.merkle_tree_append(tree, felt())
+    first_root = Cairo.merkle_tree_root(tree)
+
+    for _ <- 1..100, do: Cairo.merkle_tree_append(tree, felt())
+
+    refute Cairo.merkle_tree_known_root?(tree, first_root)
+    assert Cairo.merkle_tree_known_root?(tree, Cairo.merkle_tree_root(tree))
+    assert Cairo.merkle_tree_known_root?(tree, Cairo.eph_root())
+  end
+
+  test "merkle frontier follows the tree" do
+    tree = Cairo.merkle_tree_new()
+    frontier = Cairo.merkle_frontier_new()
+    assert Cairo.merkle_frontier_root(frontier) ==
+             Cairo.merkle_tree_root(tree)
+
+    frontier =
+      Enum.reduce(1..6, frontier, fn _, frontier ->
+        commitment = felt()
+        position = Cairo.merkle_tree_append(tree, commitment)
+
+        {frontier, ^position} =
+          Cairo.merkle_frontier_append(frontier, commitment)
+
+        assert Cairo.merkle_frontier_root(frontier) ==
+                 Cairo.merkle_tree_root(tree)
+
+        assert Cairo.merkle_frontier_path(frontier) ==
+                 Cairo.merkle_tree_path(tree, position)
+
+        frontier
+      end)
+
+    assert Cairo.merkle_frontier_size(frontier) == 6
+
+    assert {:error, {:invalid_position, 0}} =
+             Cairo.merkle_frontier_path(Cairo.merkle_frontier_new())
+
+    assert {:error, {:invalid_frontier_length, 3}} =
+             Cairo.merkle_frontier_append(<<1, 2, 3>>, felt())
+  end
+
+  test "merkle tree root matches the compliance program" do
+    {:ok, program} = File.read("./native/cairo_vm/compliance.json")
+    nf_key = felt()
+    input_resource = resource(Cairo.resource_npk(nf_key), <<0>>)
+    output_resource = resource(felt(), <<0>>)
+
+    tree = Cairo.merkle_tree_new()
+    for _ <- 1..2, do: Cairo.merkle_tree_append(tree, felt())
+
+    position =
+      Cairo.merkle_tree_append(
+        tree,
+        Cairo.resource_commitment(input_resource)
+      )
+
+    Cairo.merkle_tree_append(tree, felt())
+
+    input =
+      Cairo.generate_compliance_input_json(
+        input_resource,
+        output_resource,
+        Cairo.merkle_tree_path(tree, position),
+        position,
+        nf_key,
+        felt(),
+        felt()
+      )
+
+    {_output, trace, memory, vm_public_input} =
+      Cairo.cairo_vm_runner(program, input)
+
+    {_proof, public_input} = Cairo.prove(trace, memory, vm_public_input)
+    result = Cairo.get_compliance_output(public_input)
+
+    assert result.root == Cairo.merkle_tree_root(tree)
+  end
+end
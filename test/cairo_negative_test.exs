@@ -8,20 +8,16 @@ defmodule NegativeTest do
     invalid_program = "This is not valid JSON"
     {:ok, input} = File.read("./native/cairo_vm/cairo_input.json")
 
-    assert {:error, error_message} =
+    assert {:error, :invalid_program_content} =
              Cairo.cairo_vm_runner(invalid_program, input)
-
-    assert error_message == "Invalid program content"
   end
 
   test "cairo_vm_runner with invalid input JSON" do
     {:ok, program} = File.read("./native/cairo_vm/cairo.json")
     invalid_input = "This is not valid JSON"
 
-    assert {:error, error_message} =
+    assert {:error, :invalid_input_json} =
              Cairo.cairo_vm_runner(program, invalid_input)
-
-    assert error_message == "Invalid input JSON"
   end
 
   test "cairo_vm_runner with runtime error in Cairo program" do
@@ -31,10 +27,10 @@ defmodule NegativeTest do
 
     input = "{}"
 
-    assert {:error, error_message} =
+    assert {:error, {:runtime_error, detail}} =
              Cairo.cairo_vm_runner(program_with_error, input)
 
-    assert String.starts_with?(error_message, "Runtime error:")
+    assert is_binary(detail)
   end
 
   test "cairo_prove with invalid trace (RegisterStatesError)" do
@@ -49,10 +45,8 @@ defmodule NegativeTest do
 
     invalid_trace = [0, 1, 2, 3]
 
-    assert {:error, error_message} =
+    assert {:error, {:register_states_error, _detail}} =
              Cairo.prove(invalid_trace, memory, vm_public_input)
-
-    assert String.starts_with?(error_message, "Register states error:")
   end
 
   test "cairo_prove with invalid memory (CairoMemoryError)" do
@@ -67,10 +61,27 @@ defmodule NegativeTest do
 
     invalid_memory = [0, 1, 2, 3]
 
-    assert {:error, error_message} =
+    assert {:error, {:cairo_memory_error, _detail}} =
              Cairo.prove(trace, invalid_memory, vm_public_input)
+  end
 
-    assert String.starts_with?(error_message, "Cairo memory error:")
+  test "cairo_prove with truncated public input (PublicInputError)" do
+    {:ok, program} = File.read("./native/cairo_vm/cairo.json")
+    {:ok, input} = File.read("./native/cairo_vm/cairo_input.json")
+
+    {_output, trace, memory, vm_public_input} =
+      Cairo.cairo_vm_runner(program, input)
+
+    assert {:error,
+            {:public_input_error, %{offset: 2, reason: :truncated_rc_max}}} =
+             Cairo.prove(trace, memory, [0, 0, 1])
+
+    truncated = binary_part(vm_public_input, 0, 20)
+
+    assert {:error,
+            {:public_input_error,
+             %{offset: 20, reason: :truncated_memory_value}}} =
+             Cairo.prove(trace, memory, truncated)
   end
 
   test "cairo_verify with invalid proof" do
@@ -83,8 +94,8 @@ defmodule NegativeTest do
     {_proof, public_input} = Cairo.prove(trace, memory, vm_public_input)
     invalid_proof = [0, 1, 2, 3]
 
-    assert {:error, error_message} = Cairo.verify(invalid_proof, public_input)
-    assert String.starts_with?(error_message, "Proof decoding error:")
+    assert {:error, {:proof_decoding_error, _detail}} =
+             Cairo.verify(invalid_proof, public_input)
   end
 
   test "cairo_verify with invalid public input" do
@@ -97,7 +108,36 @@ defmodule NegativeTest do
     {proof, _public_input} = Cairo.prove(trace, memory, vm_public_input)
     invalid_public_input = []
 
-    assert {:error, error_message} = Cairo.verify(proof, invalid_public_input)
-    assert String.starts_with?(error_message, "Public input decoding error:")
+    assert {:error, {:public_input_decoding_error, _detail}} =
+             Cairo.verify(proof, invalid_public_input)
+  end
+
+  test "cairo_verify with unknown proof format version" do
+    {:ok, program} = File.read("./native/cairo_vm/cairo.json")
+    {:ok, input} = File.read("./native/cairo_vm/cairo_input.json")
+
+    {_output, trace, memory, vm_public_input} =
+      Cairo.cairo_vm_runner(program, input)
+
+    {proof, public_input} = Cairo.prove(trace, memory, vm_public_input)
+    <<magic::binary-size(4), _version::16, rest::binary>> = proof
+    future_proof = magic <> <<3, 0>> <> rest
+
+    assert {:error, {:unsupported_proof_version, _detail}} =
+             Cairo.verify(future_proof, public_input)
+  end
+
+  test "cairo_verify with a bare proof without envelope header" do
+    {:ok, program} = File.read("./native/cairo_vm/cairo.json")
+    {:ok, input} = File.read("./native/cairo_vm/cairo_input.json")
+
+    {_output, trace, memory, vm_public_input} =
+      Cairo.cairo_vm_runner(program, input)
+
+    {proof, public_input} = Cairo.prove(trace, memory, vm_public_input)
+    bare_proof = binary_part(proof, 6, byte_size(proof) - 6)
+
+    assert {:error, {:proof_decoding_error, _detail}} =
+             Cairo.verify(bare_proof, public_input)
   end
 end
@@ -0,0 +1,117 @@
+defmodule CairoProofOptionsTest do
+  use ExUnit.Case
+
+  doctest Cairo.CairoProver
+
+  setup_all do
+    {:ok, program} = File.read("./native/cairo_vm/cairo.json")
+    {:ok, input} = File.read("./native/cairo_vm/cairo_input.json")
+
+    {_output, trace, memory, vm_public_input} =
+      Cairo.cairo_vm_runner(program, input)
+
+    [trace: trace, memory: memory, vm_public_input: vm_public_input]
+  end
+
+  test "prove and verify with a chosen security level", context do
+    options = %Cairo.ProofOptions{security_level: :conjecturable_80_bits}
+
+    {proof, public_input} =
+      Cairo.prove(
+        context.trace,
+        context.memory,
+        context.vm_public_input,
+        options
+      )
+
+    assert true == Cairo.verify(proof, public_input, options)
+
+    # The default options expect a stronger proof
+    assert {:error, :proof_options_mismatch} =
+             Cairo.verify(proof, public_input)
+  end
+
+  test "verify accepts proofs stronger than the expected options", context do
+    options = %Cairo.ProofOptions{security_level: :conjecturable_128_bits}
+
+    {proof, public_input} =
+      Cairo.prove(
+        context.trace,
+        context.memory,
+        context.vm_public_input,
+        options
+      )
+
+    assert true == Cairo.verify(proof, public_input)
+  end
+
+  test "prove and verify with custom parameters", context do
+    options = %Cairo.ProofOptions{
+      security_level: :conjecturable_80_bits,
+      blowup_factor: 8,
+      fri_number_of_queries: 30
+    }
+
+    {proof, public_input} =
+      Cairo.prove(
+        context.trace,
+        context.memory,
+        context.vm_public_input,
+        options
+      )
+
+    assert true == Cairo.verify(proof, public_input, options)
+  end
+
+  test "verify rejects proofs weaker than the given options", context do
+    {proof, public_input} =
+      Cairo.prove(context.trace, context.memory, context.vm_public_input)
+
+    options = %Cairo.ProofOptions{security_level: :conjecturable_128_bits}
+
+    assert {:error, :proof_options_mismatch} =
+             Cairo.verify(proof, public_input, options)
+  end
+
+  test "prove rejects insecure custom parameters", context do
+    options = %Cairo.ProofOptions{blowup_factor: 2, fri_number_of_queries: 10}
+
+    assert {:error, {:proof_options_error, _detail}} =
+             Cairo.prove(
+               context.trace,
+               context.memory,
+               context.vm_public_input,
+               options
+             )
+  end
+
+  test "prove rejects custom parameters with provable levels", context do
+    options = %Cairo.ProofOptions{
+      security_level: :provable_80_bits,
+      blowup_factor: 16,
+      fri_number_of_queries: 30
+    }
+
+    assert {:error, {:proof_options_error, _detail}} =
+             Cairo.prove(
+               context.trace,
+               context.memory,
+               context.vm_public_input,
+               options
+             )
+  end
+
+  test "prove rejects degenerate coset offsets", context do
+    for coset_offset <- [0, 1] do
+      options = %Cairo.ProofOptions{coset_offset: coset_offset}
+
+      assert {:error, {:proof_options_error, _detail}} =
+               Cairo.prove(
+                 context.trace,
+                 context.memory,
+                 context.vm_public_input,
+                 options
+               )
+    end
+  end
+end
@@ -0,0 +1,51 @@
+defmodule CairoProveAsyncTest do
+  use ExUnit.Case
+
+  doctest Cairo.CairoProver
+
+  setup_all do
+    {:ok, program} = File.read("./native/cairo_vm/cairo.json")
+    {:ok, input} = File.read("./native/cairo_vm/cairo_input.json")
+
+    {_output, trace, memory, vm_public_input} =
+      Cairo.cairo_vm_runner(program, input)
+
+    [trace: trace, memory: memory, vm_public_input: vm_public_input]
+  end
+
+  test "prove_async sends the proof to the caller", context do
+    job =
+      Cairo.prove_async(
+        context.trace,
+        context.memory,
+        context.vm_public_input
+      )
+
+    assert_receive {:cairo_proof, ^job, {:ok, {proof, public_input}}}, 60_000
+    assert true = Cairo.verify(proof, public_input)
+    assert false == Cairo.cancel_prove(job)
+  end
+
+  test "prove_async sends errors to the caller", context do
+    job =
+      Cairo.prove_async([0, 1, 2, 3], context.memory, context.vm_public_input)
+
+    assert_receive {:cairo_proof, ^job,
+                    {:error, {:register_states_error, _detail}}},
+                   60_000
+  end
+
+  test "cancel_prove aborts the job", context do
+    job =
+      Cairo.prove_async(
+        context.trace,
+        context.memory,
+        context.vm_public_input
+      )
+
+    assert true == Cairo.cancel_prove(job)
+    assert false == Cairo.cancel_prove(job)
+
+    assert_receive {:cairo_proof, ^job, {:error, :cancelled}}, 60_000
+  end
+end
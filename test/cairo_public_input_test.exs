@@ -0,0 +1,46 @@
+defmodule CairoPublicInputTest do
+  use ExUnit.Case
+
+  doctest Cairo.CairoProver
+
+  setup_all do
+    {:ok, program} = File.read("./native/cairo_vm/cairo.json")
+    {:ok, input} = File.read("./native/cairo_vm/cairo_input.json")
+
+    {_output, trace, memory, vm_public_input} =
+      Cairo.cairo_vm_runner(program, input)
+
+    [trace: trace, memory: memory, vm_public_input: vm_public_input]
+  end
+
+  test "vm public input round trips through JSON", context do
+    json = Cairo.public_input_to_json(context.vm_public_input)
+
+    assert json =~ ~s("type": "output")
+
+    assert context.vm_public_input == Cairo.public_input_from_json(json)
+  end
+
+  test "prover public input round trips through JSON", context do
+    {proof, public_input} =
+      Cairo.prove(context.trace, context.memory, context.vm_public_input)
+
+    json = Cairo.public_input_to_json(public_input, :bincode)
+    encoded = Cairo.public_input_from_json(json, :bincode)
+
+    assert json == Cairo.public_input_to_json(encoded, :bincode)
+    assert true = Cairo.verify(proof, encoded)
+  end
+
+  test "public input JSON errors", context do
+    assert {:error,
+            {:public_input_error, %{offset: 0, reason: :truncated_rc_min}}} =
+             Cairo.public_input_to_json(<<1>>)
+
+    assert {:error, {:decoding_error, _}} =
+             Cairo.public_input_to_json(context.vm_public_input, :bincode)
+
+    assert {:error, {:invalid_json, _}} =
+             Cairo.public_input_from_json("{}", :vm)
+  end
+end
@@ -0,0 +1,145 @@
+defmodule CairoResourceTest do
+  use ExUnit.Case
+
+  doctest Cairo.CairoProver
+
+  defp felt, do: Cairo.CairoProver.cairo_random_felt()
+
+  defp resource(npk, nonce, eph) do
+    quantity = <<0::248, 5>>
+    felt() <> felt() <> quantity <> felt() <> nonce <> npk <> felt() <> eph
+  end
+
+  test "resource commitment and nullifier match the compliance program" do
+    {:ok, program} = File.read("./native/cairo_vm/compliance.json")
+    nf_key = felt()
+    rcv = felt()
+    input_resource = resource(Cairo.resource_npk(nf_key), felt(), <<1>>)
+    output_resource = resource(felt(), felt(), <<0>>)
+
+    input =
+      Cairo.generate_compliance_input_json(
+        input_resource,
+        output_resource,
+        List.duplicate(felt(), 32),
+        0,
+        nf_key,
+        felt(),
+        rcv
+      )
+
+    {_output, trace, memory, vm_public_input} =
+      Cairo.cairo_vm_runner(program, input)
+
+    {_proof, public_input} = Cairo.prove(trace, memory, vm_public_input)
+    result = Cairo.get_compliance_output(public_input)
+
+    nullifier = Cairo.resource_nullifier(input_resource, nf_key)
+    assert result.input_nullifier == nullifier
+
+    # The nonce of the output resource is the input nullifier
+    <<head::binary-size(128), _nonce::binary-size(32), tail::binary>> =
+      output_resource
+
+    assert result.output_cm ==
+             Cairo.resource_commitment(head <> nullifier <> tail)
+
+    assert result.output_cm ==
+             Cairo.resource_commitment_as_output(output_resource, nullifier)
+
+    refute result.output_cm == Cairo.resource_commitment(output_resource)
+
+    delta =
+      Cairo.transaction_delta([input_resource], [output_resource], [rcv])
+
+    assert result.delta_x <> result.delta_y == delta
+  end
+
+  test "balanced transaction delta is the blinding public key" do
+    resource = resource(felt(), felt(), <<0>>)
+    rcv = felt()
+
+    assert Cairo.get_public_key(rcv) ==
+             Cairo.transaction_delta([resource], [resource], [rcv])
+
+    assert Cairo.resource_delta(resource) ==
+             Cairo.transaction_delta([resource], [], [])
+
+    # The quantity of the resources is 5
+    assert byte_size(Cairo.resource_kind(resource)) == 64
+    assert Cairo.resource_kind(resource) != Cairo.resource_delta(resource)
+  end
+
+  test "resource psi and rcm depend on rseed and nonce" do
+    resource = resource(felt(), felt(), <<0>>)
+    psi = Cairo.resource_psi(resource)
+
+    assert byte_size(psi) == 32
+    assert psi != Cairo.resource_rcm(resource)
+    assert :binary.bin_to_list(psi) ==
+             Cairo.resource_psi(:binary.bin_to_list(resource))
+  end
+
+  test "resource NIFs reject invalid input" do
+    resource = resource(Cairo.resource_npk(felt()), felt(), <<0>>)
+
+    assert {:error, :invalid_nullifier_key} =
+             Cairo.resource_nullifier(resource, felt())
+
+    assert {:error, {:invalid_resource_length, 2}} =
+             Cairo.resource_commitment(<<1, 2>>)
+
+    assert {:error, {:invalid_felt_length, 3}} =
+             Cairo.resource_npk(<<1, 2, 3>>)
+
+    assert {:error, {:invalid_resource_length, 0}} =
+             Cairo.transaction_delta([<<>>], [], [felt()])
+
+    assert {:error, :point_at_infinity} =
+             Cairo.transaction_delta([resource], [resource], [])
+  end
+
+  test "ephemeral resources are consumed with the empty tree root" do
+    {:ok, program} = File.read("./native/cairo_vm/compliance.json")
+    {input_resource, nf_key} =
+      Cairo.ephemeral_resource(felt(), felt(), felt())
+
+    output_resource = resource(felt(), felt(), <<0>>)
+
+    assert <<_::binary-size(224), 1>> = input_resource
+    assert {other, _} = Cairo.ephemeral_resource(felt(), felt(), felt())
+    refute other == input_resource
+
+    tree = Cairo.merkle_tree_new()
+    assert Cairo.merkle_tree_root(tree) == Cairo.eph_root()
+
+    assert Cairo.merkle_verify(
+             Cairo.eph_root(),
+             <<0::256>>,
+             Cairo.dummy_merkle_path(),
+             0
+           )
+
+    input =
+      Cairo.ComplianceInput.ephemeral(
+        input_resource,
+        nf_key,
+        output_resource,
+        felt()
+      )
+
+    json = Cairo.generate_compliance_input_json(input)
+
+    {_output, trace, memory, vm_public_input} =
+      Cairo.cairo_vm_runner(program, json)
+
+    {_proof, public_input} = Cairo.prove(trace, memory, vm_public_input)
+    result = Cairo.get_compliance_output(public_input)
+
+    assert result.root == Cairo.eph_root()
+    assert Cairo.merkle_tree_known_root?(tree, result.root)
+
+    assert result.input_nullifier ==
+             Cairo.resource_nullifier(input_resource, nf_key)
+  end
+end
@@ -0,0 +1,38 @@
+defmodule CairoRunAndProveTest do
+  use ExUnit.Case
+
+  doctest Cairo.CairoProver
+
+  test "run_and_prove returns the output and a valid proof" do
+    {:ok, program} = File.read("./native/cairo_vm/cairo.json")
+    {:ok, input} = File.read("./native/cairo_vm/cairo_input.json")
+
+    {output, proof, public_input} = Cairo.run_and_prove(program, input)
+
+    assert "17\n" = output
+    assert true = Cairo.verify(proof, public_input)
+  end
+
+  test "run_and_prove with invalid program content" do
+    {:ok, input} = File.read("./native/cairo_vm/cairo_input.json")
+
+    assert {:error, :invalid_program_content} =
+             Cairo.run_and_prove("This is not valid JSON", input)
+  end
+
+  test "run_and_prove with invalid input JSON" do
+    {:ok, program} = File.read("./native/cairo_vm/cairo.json")
+
+    assert {:error, :invalid_input_json} =
+             Cairo.run_and_prove(program, "This is not valid JSON")
+  end
+
+  test "run_and_prove with invalid proof options" do
+    {:ok, program} = File.read("./native/cairo_vm/cairo.json")
+    {:ok, input} = File.read("./native/cairo_vm/cairo_input.json")
+    options = %Cairo.ProofOptions{blowup_factor: 3}
+
+    assert {:error, {:proof_options_error, _detail}} =
+             Cairo.run_and_prove(program, input, options)
+  end
+end
@@ -25,5 +25,8 @@ defmodule NifTest do
       Cairo.get_program_hash(public_input) |> Cairo.felt_to_string()
 
     # IO.inspect(program_hash)
+
+    assert Cairo.get_program_hash(public_input) ==
+             Cairo.get_program_hash_from_json(program)
   end
 end
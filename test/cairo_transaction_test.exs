@@ -0,0 +1,278 @@
+defmodule CairoTransactionTest do
+  use ExUnit.Case
+
+  doctest Cairo.CairoProver
+
+  defp felt, do: Cairo.CairoProver.cairo_random_felt()
+
+  defp prove(program, input) do
+    {_output, trace, memory, public_input} =
+      Cairo.cairo_vm_runner(program, input)
+
+    Cairo.prove(trace, memory, public_input)
+  end
+
+  # The input of trivial_resource_logic, whose merkle path starts with a
+  # left node for output resources
+  defp logic_input(resource, nf_key, is_output) do
+    <<logic::binary-32, label::binary-32, quantity::binary-32,
+      data::binary-32, nonce::binary-32, npk::binary-32, rseed::binary-32,
+      eph>> = resource
+
+    hex = &Cairo.felt_to_string/1
+
+    """
+    {"self_resource": {"logic": "#{hex.(logic)}", "label": "#{hex.(label)}",
+     "quantity": "#{hex.(quantity)}", "data": "#{hex.(data)}",
+     "eph": #{eph == 1}, "nonce": "#{hex.(nonce)}", "npk": "#{hex.(npk)}",
+     "rseed": "#{hex.(rseed)}"},
+     "resource_nf_key": "#{hex.(nf_key)}",
+     "merkle_path": [{"fst": "0x0", "snd": #{is_output}}]}
+    """
+  end
+
+  setup_all do
+    {:ok, compliance} = File.read("./native/cairo_vm/compliance.json")
+    {:ok, logic} = File.read("./native/cairo_vm/trivial_resource_logic.json")
+    logic_hash = Cairo.get_program_hash_from_json(logic)
+
+    label = felt()
+    quantity = <<0::248, 7>>
+    rcv = felt()
+
+    {input_resource, nf_key} =
+      Cairo.ephemeral_resource(logic_hash, label, quantity)
+
+    {output_resource, _} =
+      Cairo.ephemeral_resource(logic_hash, label, quantity)
+
+    compliance_input =
+      Cairo.ComplianceInput.ephemeral(
+        input_resource,
+        nf_key,
+        output_resource,
+        rcv
+      )
+
+    compliance_unit =
+      prove(
+        compliance,
+        Cairo.generate_compliance_input_json(compliance_input)
+      )
+
+    # The nonce of the output resource is the input nullifier
+    nullifier = Cairo.resource_nullifier(input_resource, nf_key)
+    <<head::binary-size(128), _::binary-32, tail::binary>> = output_resource
+
+    input_logic = prove(logic, logic_input(input_resource, nf_key, false))
+
+    output_logic =
+      prove(logic, logic_input(head <> nullifier <> tail, felt(), true))
+
+    [
+      compliance: compliance,
+      logic: logic,
+      logic_hash: logic_hash,
+      compliance_unit: compliance_unit,
+      logic_proofs: [input_logic, output_logic],
+      rcv: rcv
+    ]
+  end
+
+  test "transactions verify", context do
+    transaction =
+      Cairo.create_transaction(
+        [context.compliance_unit],
+        context.logic_proofs,
+        [context.rcv]
+      )
+
+    assert %Cairo.Transaction{} = transaction
+    assert :ok = Cairo.verify_transaction(transaction)
+  end
+
+  test "transactions without logic proofs do not verify", context do
+    [input_logic, output_logic] = context.logic_proofs
+
+    transaction =
+      Cairo.create_transaction(
+        [context.compliance_unit],
+        [output_logic],
+        [context.rcv]
+      )
+
+    assert {:error, {:missing_logic_proof, _nullifier}} =
+             Cairo.verify_transaction(transaction)
+
+    transaction = %{
+      transaction
+      | logic_proofs: [input_logic, output_logic, input_logic]
+    }
+
+    assert {:error, {:unexpected_logic_proof, 2}} =
+             Cairo.verify_transaction(transaction)
+  end
+
+  test "transactions with a resource twice do not verify", context do
+    {_proof, public_input} = context.compliance_unit
+    nullifier = Cairo.get_compliance_output(public_input).input_nullifier
+
+    transaction =
+      Cairo.create_transaction(
+        [context.compliance_unit, context.compliance_unit],
+        context.logic_proofs,
+        [context.rcv, context.rcv]
+      )
+
+    assert {:error, {:duplicate_resource, ^nullifier}} =
+             Cairo.verify_transaction(transaction)
+  end
+
+  test "transactions with logic proofs as compliance units do not verify",
+       context do
+    [input_logic, _] = context.logic_proofs
+
+    transaction =
+      Cairo.create_transaction(
+        [context.compliance_unit],
+        context.logic_proofs,
+        [context.rcv]
+      )
+
+    assert {:error, {:compliance_program_mismatch, 0}} =
+             Cairo.verify_transaction(%{
+               transaction
+               | compliance_units: [input_logic]
+             })
+  end
+
+  test "padding resources need a logic proof", context do
+    {output_resource, _} =
+      Cairo.ephemeral_resource(context.logic_hash, felt(), <<0::256>>)
+
+    [{json, rcv, %{input: {padding, nf_key}, output: nil}}] =
+      Cairo.generate_compliance_units_json([], [output_resource])
+
+    # The input is padding with the trivial resource logic
+    assert binary_part(padding, 0, 32) == context.logic_hash
+
+    nullifier = Cairo.resource_nullifier(padding, nf_key)
+    <<head::binary-size(128), _::binary-32, tail::binary>> = output_resource
+    padding_logic = prove(context.logic, logic_input(padding, nf_key, false))
+
+    output_logic =
+      prove(
+        context.logic,
+        logic_input(head <> nullifier <> tail, felt(), true)
+      )
+
+    compliance_unit = prove(context.compliance, json)
+
+    transaction =
+      Cairo.create_transaction([compliance_unit], [output_logic], [rcv])
+
+    assert {:error, {:missing_logic_proof, ^nullifier}} =
+             Cairo.verify_transaction(transaction)
+
+    transaction = %{transaction | logic_proofs: [padding_logic, output_logic]}
+    assert :ok = Cairo.verify_transaction(transaction)
+  end
+
+  test "transactions signed with other keys do not verify", context do
+    transaction =
+      Cairo.create_transaction(
+        [context.compliance_unit],
+        context.logic_proofs,
+        [felt()]
+      )
+
+    assert {:error, :invalid_binding_signature} =
+             Cairo.verify_transaction(transaction)
+  end
+
+  test "transactions with invalid proofs do not verify", context do
+    {_proof, public_input} = context.compliance_unit
+    [input_logic, {_, logic_public_input}] = context.logic_proofs
+
+    transaction =
+      Cairo.create_transaction(
+        [context.compliance_unit],
+        context.logic_proofs,
+        [context.rcv]
+      )
+
+    assert {:error,
+            {:invalid_compliance_proof, 0, {:proof_decoding_error, _}}} =
+             Cairo.verify_transaction(%{
+               transaction
+               | compliance_units: [{<<0, 1, 2, 3>>, public_input}]
+             })
+
+    assert {:error, {:invalid_logic_proof, 1, {:proof_decoding_error, _}}} =
+             Cairo.verify_transaction(%{
+               transaction
+               | logic_proofs: [input_logic, {<<>>, logic_public_input}]
+             })
+  end
+
+  test "transactions round trip through their binary encoding", context do
+    transaction =
+      Cairo.create_transaction(
+        [context.compliance_unit],
+        context.logic_proofs,
+        [context.rcv]
+      )
+
+    assert transaction.roots == [Cairo.eph_root()]
+
+    bytes = Cairo.transaction_to_bytes(transaction)
+    assert <<"AATX", 1::little-16, _::binary>> = bytes
+    assert Cairo.transaction_from_bytes(bytes) == transaction
+
+    assert {:error, {:decoding_error, _}} =
+             Cairo.transaction_from_bytes(<<"AATX", 2::little-16>>)
+
+    assert {:error, {:decoding_error, _}} =
+             Cairo.transaction_from_bytes(bytes <> <<0>>)
+
+    assert {:error, :roots_mismatch} =
+             Cairo.verify_transaction(%{transaction | roots: []})
+  end
+
+  test "partial transactions merge", context do
+    [input_logic, output_logic] = context.logic_proofs
+
+    partial =
+      Cairo.create_transaction(
+        [context.compliance_unit],
+        [input_logic],
+        [context.rcv]
+      )
+
+    # The other half has no compliance unit and no private key segment
+    other = Cairo.create_transaction([], [output_logic], [])
+
+    assert {:error, {:missing_logic_proof, _}} =
+             Cairo.verify_transaction(partial)
+
+    merged = Cairo.merge_transactions(partial, other, [context.rcv])
+
+    assert merged.compliance_units == [context.compliance_unit]
+    assert merged.logic_proofs == context.logic_proofs
+    assert :ok = Cairo.verify_transaction(merged)
+
+    # Both must match their roots and not share a resource
+    {_proof, public_input} = context.compliance_unit
+    nullifier = Cairo.get_compliance_output(public_input).input_nullifier
+
+    assert {:error, {:duplicate_resource, ^nullifier}} =
+             Cairo.merge_transactions(partial, merged, [context.rcv])
+
+    assert {:error, :roots_mismatch} =
+             Cairo.merge_transactions(
+               other,
+               %{partial | roots: []},
+               [context.rcv]
+             )
+  end
+end
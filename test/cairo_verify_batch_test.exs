@@ -0,0 +1,46 @@
+defmodule CairoVerifyBatchTest do
+  use ExUnit.Case
+
+  doctest Cairo.CairoProver
+
+  test "verify_batch verifies every proof" do
+    {:ok, program} = File.read("./native/cairo_vm/cairo.json")
+    {:ok, input} = File.read("./native/cairo_vm/cairo_input.json")
+
+    {_output, trace, memory, vm_public_input} =
+      Cairo.cairo_vm_runner(program, input)
+
+    {proof, public_input} = Cairo.prove(trace, memory, vm_public_input)
+
+    assert {[{:ok, true}, {:ok, true}], nil} =
+             Cairo.verify_batch([
+               {proof, public_input},
+               {proof, public_input}
+             ])
+  end
+
+  test "verify_batch reports the first failure" do
+    {:ok, program} = File.read("./native/cairo_vm/cairo.json")
+    {:ok, input} = File.read("./native/cairo_vm/cairo_input.json")
+
+    {_output, trace, memory, vm_public_input} =
+      Cairo.cairo_vm_runner(program, input)
+
+    {proof, public_input} = Cairo.prove(trace, memory, vm_public_input)
+
+    assert {[
+              {:ok, true},
+              {:error, {:proof_decoding_error, _}},
+              {:error, {:public_input_decoding_error, _}}
+            ], 1} =
+             Cairo.verify_batch([
+               {proof, public_input},
+               {[0, 1, 2, 3], public_input},
+               {proof, []}
+             ])
+  end
+
+  test "verify_batch with an empty list" do
+    assert {[], nil} = Cairo.verify_batch([])
+  end
+end
@@ -71,4 +71,30 @@ defmodule PoseidonTest do
 
     assert hash_bytes == output
   end
+
+  test "poseidon_hash_binary_test" do
+    x =
+      Base.decode16!(
+        "000b662f9017fa7956fd70e26129b1833e10ad000fd37b4d9f4e0ce6884b7bbe",
+        case: :lower
+      )
+
+    y =
+      Base.decode16!(
+        "001fe356bf76102cdae1bfbdc173602ead228b12904c00dad9cf16e035468bea",
+        case: :lower
+      )
+
+    hash =
+      Base.decode16!(
+        "075540825a6ecc5dc7d7c2f5f868164182742227f1367d66c43ee51ec7937a81",
+        case: :lower
+      )
+
+    assert hash == Cairo.poseidon(x, y)
+    assert hash == Cairo.CairoProver.poseidon(x, y)
+
+    assert :binary.bin_to_list(hash) ==
+             Cairo.poseidon(:binary.bin_to_list(x), :binary.bin_to_list(y))
+  end
 end